@@ -0,0 +1,161 @@
+use super::{
+    get_eac_displacement, get_eac_register_names, get_far_segment, get_literal_value, get_mnemonic,
+    get_prefixes, get_register_name, get_shown_operands, is_int_3, FormatOptions, Formatter,
+};
+use crate::{
+    op_code::op::OpCode,
+    program::instruction::{Instruction, InstructionOperand, OperandFormat, OperandType},
+    register,
+};
+
+/// GNU assembler AT&T syntax: `%` registers, `$` immediates and the source operand first.
+pub struct AttFormatter {
+    pub options: FormatOptions,
+}
+
+impl Formatter for AttFormatter {
+    fn header(&self) -> &'static str {
+        ".code16"
+    }
+
+    fn format(&self, instruction: &Instruction, target_label: Option<&str>) -> String {
+        // The two byte INT 3 can only be kept as data.
+        if is_int_3(instruction) {
+            return format!(
+                ".byte 0x{}, 0x{}",
+                self.options.hex_byte(0xCD),
+                self.options.hex_byte(3)
+            );
+        }
+
+        let mut string = get_prefixes(instruction, &self.options, true);
+
+        let operands = get_shown_operands(instruction);
+        let mut mnemonic = match instruction.op_code {
+            OpCode::CallFar => String::from("LCALL"),
+            OpCode::JmpFar => String::from("LJMP"),
+            OpCode::Retf => String::from("LRET"),
+            OpCode::Cbw => String::from("CBTW"),
+            OpCode::Cwd => String::from("CWTD"),
+            _ => get_mnemonic(instruction),
+        };
+        // The size is given by the mnemonic, not the memory operand.
+        if let Some(operand) = operands
+            .iter()
+            .find(|operand| operand.format == OperandFormat::Sized)
+        {
+            mnemonic.push(if operand.register_word == Some(true) {
+                'W'
+            } else {
+                'B'
+            });
+        }
+        string.push_str(&self.options.case(&mnemonic));
+
+        let operand_strings: Vec<String> = operands
+            .iter()
+            .rev()
+            .map(|operand| self.format_operand(instruction, operand, target_label))
+            .collect();
+        if !operand_strings.is_empty() {
+            string.push(' ');
+            string.push_str(&operand_strings.join(","));
+        }
+
+        string
+    }
+
+    fn format_data(&self, byte: u8) -> String {
+        format!(".byte 0x{}", self.options.hex_byte(byte))
+    }
+}
+
+impl AttFormatter {
+    fn format_operand(
+        &self,
+        instruction: &Instruction,
+        operand: &InstructionOperand,
+        target_label: Option<&str>,
+    ) -> String {
+        let string = match operand.operand_type {
+            // IN and OUT take the port in DX as if it were memory.
+            OperandType::REGISTER
+                if matches!(instruction.op_code, OpCode::In | OpCode::Out)
+                    && operand.register == Some(register::word::DX) =>
+            {
+                format!("({})", self.register(operand))
+            }
+            OperandType::REGISTER | OperandType::SEGMENT => self.register(operand),
+            OperandType::EAC => self.format_memory(operand),
+            OperandType::LITERAL => {
+                return self.format_literal(instruction, operand, target_label);
+            }
+        };
+
+        // Indirect branches take their target from a register or memory.
+        if matches!(
+            instruction.op_code,
+            OpCode::Call | OpCode::CallFar | OpCode::Jmp | OpCode::JmpFar
+        ) {
+            format!("*{}", string)
+        } else {
+            string
+        }
+    }
+
+    fn register(&self, operand: &InstructionOperand) -> String {
+        format!("%{}", self.options.case(&get_register_name(operand)))
+    }
+
+    fn format_memory(&self, operand: &InstructionOperand) -> String {
+        let mut string = String::new();
+
+        if let Some(segment) = operand.eac_segment {
+            string.push('%');
+            string.push_str(&self.options.case(&register::segment::get_str(segment)));
+            string.push(':');
+        }
+
+        if let Some(displacement) = get_eac_displacement(operand) {
+            string.push_str(&self.number(displacement));
+        }
+
+        let registers = get_eac_register_names(operand);
+        if !registers.is_empty() {
+            let registers: Vec<String> = registers
+                .iter()
+                .map(|r| format!("%{}", self.options.case(r)))
+                .collect();
+            string.push('(');
+            string.push_str(&registers.join(","));
+            string.push(')');
+        }
+
+        string
+    }
+
+    fn format_literal(
+        &self,
+        instruction: &Instruction,
+        operand: &InstructionOperand,
+        target_label: Option<&str>,
+    ) -> String {
+        let value = get_literal_value(operand);
+        match operand.format {
+            OperandFormat::Relative => match target_label {
+                Some(label) => label.to_string(),
+                None => format!(".{:+}", value),
+            },
+            OperandFormat::FarAddress => format!(
+                "${},${}",
+                self.number(get_far_segment(instruction)),
+                self.number(value)
+            ),
+            _ => format!("${}", self.number(value)),
+        }
+    }
+
+    fn number(&self, value: i32) -> String {
+        self.options.number(value, |digits| format!("0x{}", digits))
+    }
+}
@@ -0,0 +1,204 @@
+use super::{
+    super::is_string_instruction, get_eac_displacement, get_eac_register_names, get_far_segment,
+    get_literal_value, get_mnemonic, get_prefixes, get_register_name, get_shown_operands, is_int_3,
+    is_near_jump, FormatOptions, Formatter,
+};
+use crate::{
+    op_code::{self, op::OpCode},
+    program::instruction::{Instruction, InstructionOperand, OperandFormat, OperandType},
+    register,
+};
+
+/// MASM syntax, also understood by TASM. Keywords (`ptr`, `near`...) follow the case option.
+pub struct MasmFormatter {
+    pub options: FormatOptions,
+}
+
+impl Formatter for MasmFormatter {
+    fn header(&self) -> &'static str {
+        ".8086"
+    }
+
+    fn format(&self, instruction: &Instruction, target_label: Option<&str>) -> String {
+        // The two byte INT 3 can only be kept as data.
+        if is_int_3(instruction) {
+            return format!(
+                "{} {}, {}",
+                self.options.case("DB"),
+                self.hex_byte(0xCD),
+                self.hex_byte(3)
+            );
+        }
+
+        let mut string = String::new();
+
+        // MASM writes segment overrides on memory operands, the ones that don't apply to any
+        // (e.g. on STOS, which always writes to ES:DI) can only be data.
+        let has_segment_operand = [instruction.dest_operand, instruction.src_operand]
+            .into_iter()
+            .flatten()
+            .any(|operand| operand.eac_segment.is_some());
+        if let Some(segment) = instruction.segment_prefix {
+            if !has_segment_operand {
+                let prefix = op_code::prefix::SEGMENT_ES | segment << 3;
+                string.push_str(&self.format_data(prefix));
+                string.push('\n');
+            }
+        }
+
+        string.push_str(&get_prefixes(instruction, &self.options, false));
+
+        if instruction.op_code == OpCode::Int3 {
+            string.push_str(&self.options.case("INT 3"));
+            return string;
+        }
+
+        // String instructions and XLAT only take an override in their explicit form, with
+        // operands that give the size and segment instead of the mnemonic suffix.
+        let explicit = has_segment_operand
+            && (is_string_instruction(instruction.op_code) || instruction.op_code == OpCode::Xlat);
+        let operand_strings: Vec<String> = if explicit {
+            string.push_str(
+                &self
+                    .options
+                    .case(op_code::strings::get_str(instruction.op_code)),
+            );
+            [instruction.dest_operand, instruction.src_operand]
+                .into_iter()
+                .flatten()
+                .filter(|operand| matches!(operand.operand_type, OperandType::EAC))
+                .map(|operand| self.format_memory(&operand, true))
+                .collect()
+        } else {
+            string.push_str(&self.options.case(&get_mnemonic(instruction)));
+            get_shown_operands(instruction)
+                .iter()
+                .map(|operand| self.format_operand(instruction, operand, target_label))
+                .collect()
+        };
+        if !operand_strings.is_empty() {
+            string.push(' ');
+            string.push_str(&operand_strings.join(", "));
+        }
+
+        string
+    }
+
+    fn format_data(&self, byte: u8) -> String {
+        format!("{} {}", self.options.case("DB"), self.hex_byte(byte))
+    }
+}
+
+impl MasmFormatter {
+    fn format_operand(
+        &self,
+        instruction: &Instruction,
+        operand: &InstructionOperand,
+        target_label: Option<&str>,
+    ) -> String {
+        match operand.operand_type {
+            OperandType::REGISTER | OperandType::SEGMENT => {
+                self.options.case(&get_register_name(operand))
+            }
+            OperandType::EAC => self.format_memory(operand, false),
+            OperandType::LITERAL => self.format_literal(instruction, operand, target_label),
+        }
+    }
+
+    /// Formats a memory operand, with its size and segment always given if `explicit`.
+    fn format_memory(&self, operand: &InstructionOperand, explicit: bool) -> String {
+        let mut string = if explicit || operand.format == OperandFormat::Sized {
+            if operand.register_word == Some(true) {
+                self.options.case("WORD PTR ")
+            } else {
+                self.options.case("BYTE PTR ")
+            }
+        } else if operand.format == OperandFormat::Far {
+            self.options.case("DWORD PTR ")
+        } else {
+            String::new()
+        };
+
+        let registers = get_eac_register_names(operand);
+        let displacement = get_eac_displacement(operand);
+
+        // A number in brackets is an immediate for MASM, unless it has a segment. String
+        // instructions always address DI in ES.
+        let is_string_di = explicit && operand.eac_reg_0 == Some(register::word::DI);
+        let segment = match operand.eac_segment {
+            Some(segment) => Some(segment),
+            None if is_string_di => Some(register::segment::ES),
+            None if registers.is_empty() => Some(register::segment::DS),
+            None => None,
+        };
+        if let Some(segment) = segment {
+            string.push_str(&self.options.case(&register::segment::get_str(segment)));
+            string.push(':');
+        }
+        string.push('[');
+
+        let registers: Vec<String> = registers.iter().map(|r| self.options.case(r)).collect();
+        string.push_str(&registers.join(" + "));
+
+        match displacement {
+            Some(displacement) if registers.is_empty() => {
+                string.push_str(&self.number(displacement))
+            }
+            Some(displacement) => {
+                string.push_str(if displacement < 0 { " - " } else { " + " });
+                string.push_str(&self.number(displacement.abs()));
+            }
+            None => (),
+        }
+
+        string.push(']');
+        string
+    }
+
+    fn format_literal(
+        &self,
+        instruction: &Instruction,
+        operand: &InstructionOperand,
+        target_label: Option<&str>,
+    ) -> String {
+        let value = get_literal_value(operand);
+        match operand.format {
+            OperandFormat::Relative => {
+                let target = match target_label {
+                    Some(label) => label.to_string(),
+                    None => format!("${:+}", value),
+                };
+                if is_near_jump(instruction, operand) {
+                    format!("{}{}", self.options.case("NEAR PTR "), target)
+                } else {
+                    target
+                }
+            }
+            OperandFormat::FarAddress => format!(
+                "{}{}:{}",
+                self.options.case("FAR PTR "),
+                self.number(get_far_segment(instruction)),
+                self.number(value)
+            ),
+            _ => self.number(value),
+        }
+    }
+
+    /// Formats `byte` in hex, whatever the number base of the other numbers.
+    fn hex_byte(&self, byte: u8) -> String {
+        add_hex_suffix(self.options.hex_byte(byte))
+    }
+
+    fn number(&self, value: i32) -> String {
+        self.options.number(value, add_hex_suffix)
+    }
+}
+
+/// Hex numbers take an `h` suffix, and a leading 0 when they start with a letter.
+fn add_hex_suffix(digits: String) -> String {
+    if digits.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        format!("0{}h", digits)
+    } else {
+        format!("{}h", digits)
+    }
+}
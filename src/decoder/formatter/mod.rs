@@ -0,0 +1,223 @@
+mod att;
+mod masm;
+mod nasm;
+
+use crate::{
+    op_code::{self, op::OpCode},
+    program::instruction::{Instruction, InstructionOperand, OperandFormat, OperandType},
+    register::{self, util::get_register_string},
+};
+
+use super::is_string_instruction;
+
+/// Assembler syntax of the decoder output.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Syntax {
+    Nasm,
+    /// MASM and TASM.
+    Masm,
+    /// GNU assembler AT&T syntax.
+    Att,
+}
+
+#[derive(Clone, Copy)]
+pub struct FormatOptions {
+    pub syntax: Syntax,
+    /// Whether mnemonics, prefixes, registers and hex digits are upper case.
+    pub uppercase: bool,
+    /// Whether numbers are in hex instead of decimal.
+    pub hex: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            syntax: Syntax::Nasm,
+            uppercase: true,
+            hex: false,
+        }
+    }
+}
+
+impl FormatOptions {
+    pub fn formatter(self) -> Box<dyn Formatter> {
+        match self.syntax {
+            Syntax::Nasm => Box::new(nasm::NasmFormatter { options: self }),
+            Syntax::Masm => Box::new(masm::MasmFormatter { options: self }),
+            Syntax::Att => Box::new(att::AttFormatter { options: self }),
+        }
+    }
+
+    /// Returns `name` (a mnemonic, prefix or register) in the selected case.
+    fn case(&self, name: &str) -> String {
+        if self.uppercase {
+            name.to_uppercase()
+        } else {
+            name.to_lowercase()
+        }
+    }
+
+    /// Returns the hex digits of `value`, in the selected case.
+    fn hex_digits(&self, value: u32) -> String {
+        if self.uppercase {
+            format!("{:X}", value)
+        } else {
+            format!("{:x}", value)
+        }
+    }
+
+    /// Returns the two hex digits of `byte`, in the selected case.
+    pub fn hex_byte(&self, byte: u8) -> String {
+        if self.uppercase {
+            format!("{:02X}", byte)
+        } else {
+            format!("{:02x}", byte)
+        }
+    }
+
+    /// Returns the four hex digits of `word`, in the selected case.
+    pub fn hex_word(&self, word: u16) -> String {
+        if self.uppercase {
+            format!("{:04X}", word)
+        } else {
+            format!("{:04x}", word)
+        }
+    }
+
+    /// Formats `value` in decimal, or in hex with `format_hex` applied to its digits.
+    fn number(&self, value: i32, format_hex: impl Fn(String) -> String) -> String {
+        let sign = if value < 0 { "-" } else { "" };
+        let digits = if self.hex {
+            format_hex(self.hex_digits(value.unsigned_abs()))
+        } else {
+            value.unsigned_abs().to_string()
+        };
+
+        format!("{}{}", sign, digits)
+    }
+}
+
+/// Formats decoded instructions as the source of an assembler.
+pub trait Formatter {
+    /// Directive the output starts with, setting up the assembler for 16 bit code.
+    fn header(&self) -> &'static str;
+
+    /// Formats `instruction`, using `target_label` instead of the increment of a direct branch.
+    fn format(&self, instruction: &Instruction, target_label: Option<&str>) -> String;
+
+    /// Formats a byte that couldn't be decoded as data.
+    fn format_data(&self, byte: u8) -> String;
+}
+
+/// Returns the mnemonic of `instruction`, with the size suffix of string instructions.
+fn get_mnemonic(instruction: &Instruction) -> String {
+    let mut mnemonic = op_code::strings::get_str(instruction.op_code).to_string();
+    if is_string_instruction(instruction.op_code) {
+        let word = instruction
+            .dest_operand
+            .and_then(|operand| operand.register_word)
+            .unwrap_or_default();
+        mnemonic.push(if word { 'W' } else { 'B' });
+    }
+
+    mnemonic
+}
+
+/// Returns the prefixes of `instruction` in the order they are written, each followed by a space.
+/// Segment overrides are only a prefix, if `bare_segment`, when there is no shown memory operand
+/// to put them on.
+fn get_prefixes(instruction: &Instruction, options: &FormatOptions, bare_segment: bool) -> String {
+    let mut prefixes = String::new();
+
+    if instruction.lock_prefix {
+        prefixes.push_str(&options.case("LOCK "));
+    }
+    if let Some(segment) = instruction.segment_prefix.filter(|_| bare_segment) {
+        let has_memory_operand = get_shown_operands(instruction)
+            .iter()
+            .any(|operand| matches!(operand.operand_type, OperandType::EAC));
+        if !has_memory_operand {
+            prefixes.push_str(&options.case(&register::segment::get_str(segment)));
+            prefixes.push(' ');
+        }
+    }
+    if let Some(rep_prefix) = instruction.rep_prefix {
+        prefixes.push_str(&options.case(op_code::strings::get_rep_prefix_str(
+            rep_prefix,
+            instruction.op_code,
+        )));
+        prefixes.push(' ');
+    }
+
+    prefixes
+}
+
+/// Returns the operands of `instruction` that are shown, destination first.
+fn get_shown_operands(instruction: &Instruction) -> Vec<InstructionOperand> {
+    [instruction.dest_operand, instruction.src_operand]
+        .into_iter()
+        .flatten()
+        .filter(|operand| operand.format != OperandFormat::Hidden)
+        .collect()
+}
+
+/// Returns the name of a register or segment register operand.
+fn get_register_name(operand: &InstructionOperand) -> String {
+    let register = operand.register.unwrap();
+    match operand.operand_type {
+        OperandType::SEGMENT => register::segment::get_str(register),
+        _ => get_register_string(register, operand.register_word.unwrap()),
+    }
+}
+
+/// Returns the names of the registers of a memory operand.
+fn get_eac_register_names(operand: &InstructionOperand) -> Vec<String> {
+    [operand.eac_reg_0, operand.eac_reg_1]
+        .into_iter()
+        .flatten()
+        .map(register::word::get_str)
+        .collect()
+}
+
+/// Returns the displacement of a memory operand, signed unless it's a direct address.
+fn get_eac_displacement(operand: &InstructionOperand) -> Option<i32> {
+    let displacement = operand.eac_displacement?;
+    if operand.eac_reg_0.is_none() {
+        Some(displacement as i32)
+    } else {
+        Some(displacement as i16 as i32)
+    }
+}
+
+/// Returns the value of a literal operand, signed if it was sign extended or is an increment.
+fn get_literal_value(operand: &InstructionOperand) -> i32 {
+    let literal = operand.literal.unwrap();
+    match operand.format {
+        OperandFormat::Signed | OperandFormat::Relative => literal as i16 as i32,
+        _ => literal as i32,
+    }
+}
+
+/// Whether the operand is a near JMP increment, which assemblers would shorten when they can.
+fn is_near_jump(instruction: &Instruction, operand: &InstructionOperand) -> bool {
+    instruction.op_code == OpCode::Jmp
+        && operand.format == OperandFormat::Relative
+        && operand.register_word == Some(true)
+}
+
+/// Whether `instruction` is the two byte INT 3, which assemblers encode as the single byte INT3.
+fn is_int_3(instruction: &Instruction) -> bool {
+    instruction.op_code == OpCode::Int
+        && instruction
+            .dest_operand
+            .and_then(|operand| operand.literal)
+            .is_some_and(|literal| literal == 3)
+}
+
+/// Returns the segment of a far address, kept in the operand after its offset.
+fn get_far_segment(instruction: &Instruction) -> i32 {
+    instruction
+        .src_operand
+        .and_then(|operand| operand.literal)
+        .unwrap_or_default() as i32
+}
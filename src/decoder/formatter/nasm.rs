@@ -0,0 +1,142 @@
+use super::{
+    get_eac_displacement, get_eac_register_names, get_far_segment, get_literal_value, get_mnemonic,
+    get_prefixes, get_register_name, get_shown_operands, is_near_jump, FormatOptions, Formatter,
+};
+use crate::{
+    program::instruction::{Instruction, InstructionOperand, OperandFormat, OperandType},
+    register,
+};
+
+/// NASM syntax, which reassembles to the same bytes for the encodings NASM picks.
+pub struct NasmFormatter {
+    pub options: FormatOptions,
+}
+
+impl Formatter for NasmFormatter {
+    fn header(&self) -> &'static str {
+        "bits 16"
+    }
+
+    fn format(&self, instruction: &Instruction, target_label: Option<&str>) -> String {
+        let mut string = get_prefixes(instruction, &self.options, true);
+        string.push_str(&self.options.case(&get_mnemonic(instruction)));
+
+        let operand_strings: Vec<String> = get_shown_operands(instruction)
+            .iter()
+            .map(|operand| self.format_operand(instruction, operand, target_label))
+            .collect();
+        if !operand_strings.is_empty() {
+            string.push(' ');
+            string.push_str(&operand_strings.join(", "));
+        }
+
+        string
+    }
+
+    fn format_data(&self, byte: u8) -> String {
+        format!(
+            "{} 0x{}",
+            self.options.case("DB"),
+            self.options.hex_byte(byte)
+        )
+    }
+}
+
+impl NasmFormatter {
+    fn format_operand(
+        &self,
+        instruction: &Instruction,
+        operand: &InstructionOperand,
+        target_label: Option<&str>,
+    ) -> String {
+        match operand.operand_type {
+            OperandType::REGISTER | OperandType::SEGMENT => {
+                self.options.case(&get_register_name(operand))
+            }
+            OperandType::EAC => self.format_memory(operand),
+            OperandType::LITERAL => self.format_literal(instruction, operand, target_label),
+        }
+    }
+
+    fn format_memory(&self, operand: &InstructionOperand) -> String {
+        let mut string = match operand.format {
+            OperandFormat::Sized if operand.register_word == Some(true) => String::from("word "),
+            OperandFormat::Sized => String::from("byte "),
+            OperandFormat::Far => String::from("far "),
+            _ => String::new(),
+        };
+
+        if let Some(segment) = operand.eac_segment {
+            string.push_str(&self.options.case(&register::segment::get_str(segment)));
+            string.push(':');
+        }
+        string.push('[');
+
+        let registers = get_eac_register_names(operand);
+        let displacement = get_eac_displacement(operand);
+
+        // Displacements NASM would encode shorter get an explicit size.
+        if let Some(displacement) = displacement.filter(|_| !registers.is_empty()) {
+            // [BP] can only be encoded with a displacement.
+            let is_bp =
+                operand.eac_reg_0 == Some(register::word::BP) && operand.eac_reg_1.is_none();
+            if !operand.eac_displacement_word && displacement == 0 && !is_bp {
+                string.push_str("byte ");
+            } else if operand.eac_displacement_word && i8::try_from(displacement).is_ok() {
+                string.push_str("word ");
+            }
+        }
+
+        let registers: Vec<String> = registers.iter().map(|r| self.options.case(r)).collect();
+        string.push_str(&registers.join(" + "));
+
+        match displacement {
+            Some(displacement) if registers.is_empty() => {
+                string.push_str(&self.number(displacement))
+            }
+            Some(displacement) => {
+                string.push_str(if displacement < 0 { " - " } else { " + " });
+                string.push_str(&self.number(displacement.abs()));
+            }
+            None => (),
+        }
+
+        string.push(']');
+        string
+    }
+
+    fn format_literal(
+        &self,
+        instruction: &Instruction,
+        operand: &InstructionOperand,
+        target_label: Option<&str>,
+    ) -> String {
+        let value = get_literal_value(operand);
+        match operand.format {
+            OperandFormat::Relative => {
+                let target = match target_label {
+                    Some(label) => label.to_string(),
+                    None => format!("${:+}", value),
+                };
+                // A near JMP is only kept as such by NASM if asked explicitly
+                if is_near_jump(instruction, operand) {
+                    format!("near {}", target)
+                } else {
+                    target
+                }
+            }
+            OperandFormat::FarAddress => format!(
+                "{}:{}",
+                self.number(get_far_segment(instruction)),
+                self.number(value)
+            ),
+            // NASM sign extends a byte whenever it can, unless the word is `strict`.
+            OperandFormat::StrictWord => format!("strict word {}", self.number(value)),
+            _ => self.number(value),
+        }
+    }
+
+    fn number(&self, value: i32) -> String {
+        self.options.number(value, |digits| format!("0x{}", digits))
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    op_code::op::OpCode,
+    program::{
+        instruction::{Instruction, OperandType},
+        program::Program,
+    },
+};
+
+/// Labels for the direct branch targets of a program, so branches can use them instead of `$`
+/// relative increments. Targets that aren't the start of a decoded instruction get no label.
+pub struct Labels {
+    targets: BTreeSet<usize>,
+}
+
+impl Labels {
+    pub fn new(program: &Program) -> Self {
+        let targets = program
+            .instructions_in_order()
+            .filter_map(get_branch_target)
+            .filter(|target| program.instructions.contains_key(target))
+            .collect();
+
+        Self { targets }
+    }
+
+    /// Returns the label of the instruction at `start_byte`, if something branches to it.
+    pub fn get_label_at(&self, start_byte: usize) -> Option<String> {
+        self.targets
+            .contains(&start_byte)
+            .then(|| get_label(start_byte))
+    }
+
+    /// Returns the label of the branch target of `instruction`, if it has one.
+    pub fn get_target_label(&self, instruction: &Instruction) -> Option<String> {
+        get_branch_target(instruction).and_then(|target| self.get_label_at(target))
+    }
+}
+
+fn get_label(start_byte: usize) -> String {
+    format!("label_{:04x}", start_byte)
+}
+
+/// Returns the byte a direct CALL/JMP, conditional jump or loop branches to.
+fn get_branch_target(instruction: &Instruction) -> Option<usize> {
+    if !matches!(
+        instruction.op_code,
+        OpCode::Call
+            | OpCode::Jmp
+            | OpCode::Je
+            | OpCode::Jl
+            | OpCode::Jle
+            | OpCode::Jb
+            | OpCode::Jbe
+            | OpCode::Jp
+            | OpCode::Jo
+            | OpCode::Js
+            | OpCode::Jnz
+            | OpCode::Jnl
+            | OpCode::Jg
+            | OpCode::Jnb
+            | OpCode::Ja
+            | OpCode::Jnp
+            | OpCode::Jno
+            | OpCode::Jns
+            | OpCode::Loop
+            | OpCode::Loopz
+            | OpCode::Loopnz
+            | OpCode::Jcxz
+    ) {
+        return None;
+    }
+
+    // Indirect CALL/JMP take their target from a register or memory.
+    let dest_operand = instruction.dest_operand?;
+    if !matches!(dest_operand.operand_type, OperandType::LITERAL) {
+        return None;
+    }
+
+    // The increment is relative to the start of the instruction, prefixes included, so the
+    // displacement taken from its end is what's left of it past the instruction bytes.
+    let increment = dest_operand.literal? as i16;
+    let displacement = increment as isize - instruction.length as isize;
+    (instruction.start_byte + instruction.length).checked_add_signed(displacement)
+}
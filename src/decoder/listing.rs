@@ -0,0 +1,69 @@
+use super::formatter::FormatOptions;
+use crate::program::instruction::Instruction;
+
+/// Width of the raw bytes column: the longest instruction plus a couple of prefixes.
+const BYTES_COLUMN_WIDTH: usize = 16;
+/// Width of the instruction column when followed by the cycles column.
+const TEXT_COLUMN_WIDTH: usize = 32;
+
+/// Pushes the listing line of `instruction` to `output`: offset, raw bytes, `decoded_string`
+/// and, if `print_cycles`, the cycles estimation. Hex digits follow the case of `options`.
+pub fn push_instruction_line(
+    output: &mut String,
+    instruction: &Instruction,
+    bytes: &[u8],
+    decoded_string: &str,
+    print_cycles: bool,
+    options: &FormatOptions,
+) {
+    let instruction_bytes =
+        &bytes[instruction.start_byte..instruction.start_byte + instruction.length];
+    let bytes_string: String = instruction_bytes
+        .iter()
+        .map(|byte| options.hex_byte(*byte))
+        .collect();
+
+    let mut line = format!(
+        "{}  {:<width$}",
+        options.hex_word(instruction.start_byte as u16),
+        bytes_string,
+        width = BYTES_COLUMN_WIDTH
+    );
+    line.push_str(decoded_string);
+
+    if print_cycles {
+        if let Some(time_estimation) = instruction.time_estimation {
+            let padding = TEXT_COLUMN_WIDTH.saturating_sub(decoded_string.len());
+            line.push_str(&" ".repeat(padding));
+            line.push_str(" ; ");
+            line.push_str(&time_estimation.get_string());
+        }
+    }
+
+    output.push_str(line.trim_end());
+    output.push('\n');
+}
+
+/// Pushes a label line to `output`, with empty offset and bytes columns.
+pub fn push_label_line(output: &mut String, label: &str) {
+    output.push_str(&" ".repeat(6 + BYTES_COLUMN_WIDTH));
+    output.push_str(label);
+    output.push_str(":\n");
+}
+
+/// Pushes the listing line of a `byte` at `offset` that couldn't be decoded, shown as `data_string`.
+pub fn push_data_line(
+    output: &mut String,
+    offset: usize,
+    byte: u8,
+    data_string: &str,
+    options: &FormatOptions,
+) {
+    output.push_str(&format!(
+        "{}  {:<width$}{}\n",
+        options.hex_word(offset as u16),
+        options.hex_byte(byte),
+        data_string,
+        width = BYTES_COLUMN_WIDTH
+    ));
+}
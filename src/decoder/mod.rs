@@ -0,0 +1,599 @@
+use std::fs;
+
+pub mod formatter;
+mod labels;
+mod listing;
+mod table;
+
+use crate::{
+    displacement_mode,
+    effective_address_calculation::get_eac_operand,
+    op_code::{self, op::OpCode},
+    program::{
+        instruction::{
+            Instruction, InstructionOperand, InstructionTime, OperandFormat, OperandType, RepPrefix,
+        },
+        program::Program,
+    },
+    register::{
+        self,
+        util::{get_register_operand, get_segment_register_operand},
+    },
+};
+
+use formatter::FormatOptions;
+use labels::Labels;
+use table::{Encoding, Field, Operand, INSTRUCTION_TABLE};
+
+/// Decodes an asm file and returns a `Program` with the decoded instructions.
+/// If `labels`, the output uses generated labels for branch targets.
+/// If `listing`, the output is a listing with the offset and bytes of each instruction, and its
+/// cycles estimation if `print_cycles`.
+/// `format_options` selects the syntax of the output.
+/// If `resilient`, bytes that can't be decoded are output as data and decoding goes on after
+/// them, otherwise decoding stops at the first one.
+pub fn decode(
+    file_name: &str,
+    print: bool,
+    labels: bool,
+    listing: bool,
+    print_cycles: bool,
+    format_options: FormatOptions,
+    resilient: bool,
+) -> Result<Program, ()> {
+    println!("Decoder started with {}", file_name);
+
+    let bytes = &fs::read(file_name).unwrap();
+    let bytes_len = bytes.len();
+    let mut curr_byte: usize = 0;
+    let mut undecoded_bytes: usize = 0;
+
+    let mut program = Program::new();
+
+    while curr_byte < bytes_len {
+        let (instruction_length, instruction) = match decode_instruction(bytes, curr_byte) {
+            Ok(decoded) => decoded,
+            // Skip a single byte, so the next one is tried as the start of an instruction.
+            Err(_) if resilient => {
+                curr_byte += 1;
+                undecoded_bytes += 1;
+                continue;
+            }
+            Err(_) => {
+                undecoded_bytes = bytes_len - curr_byte;
+                break;
+            }
+        };
+
+        curr_byte += instruction_length;
+
+        program.insert_instruction(instruction);
+    }
+
+    // Labels need every instruction decoded first, to know where the branches land.
+    let labels = labels.then(|| Labels::new(&program));
+
+    let formatter = format_options.formatter();
+    let mut output: String = Default::default();
+
+    if !listing {
+        output.push_str(formatter.header());
+        output.push_str("\n\n");
+    }
+
+    // Anything up to `curr_byte` that isn't an instruction is a byte that couldn't be decoded.
+    let mut offset = 0;
+    while offset < curr_byte {
+        let Some(instruction) = program.instructions.get(&offset) else {
+            let data_string = formatter.format_data(bytes[offset]);
+            if listing {
+                listing::push_data_line(
+                    &mut output,
+                    offset,
+                    bytes[offset],
+                    &data_string,
+                    &format_options,
+                );
+            } else {
+                output.push_str(&data_string);
+                output.push('\n');
+            }
+            offset += 1;
+            continue;
+        };
+        offset += instruction.length;
+
+        let (label, target_label) = match &labels {
+            Some(labels) => (
+                labels.get_label_at(instruction.start_byte),
+                labels.get_target_label(instruction),
+            ),
+            None => (None, None),
+        };
+        let decoded_string = formatter.format(instruction, target_label.as_deref());
+
+        if listing {
+            if let Some(label) = label {
+                listing::push_label_line(&mut output, &label);
+            }
+            listing::push_instruction_line(
+                &mut output,
+                instruction,
+                bytes,
+                &decoded_string,
+                print_cycles,
+                &format_options,
+            );
+        } else {
+            if let Some(label) = label {
+                output.push_str(&label);
+                output.push_str(":\n");
+            }
+            output.push_str(&decoded_string);
+            output.push('\n');
+        }
+    }
+
+    if print {
+        println!("{}", &output);
+    } else {
+        println!("Skipping decoder output...")
+    }
+
+    if undecoded_bytes > 0 {
+        // Not part of the output, which is meant to be assembled again.
+        eprintln!("{} of {} bytes left undecoded", undecoded_bytes, bytes_len);
+    }
+
+    Ok(program)
+}
+
+/// Decodes the instruction starting at `curr_byte`, including its prefixes.
+/// Returns instruction length in bytes and the decoded instruction.
+pub fn decode_instruction(bytes: &[u8], curr_byte: usize) -> Result<(usize, Instruction), ()> {
+    let bytes_len = bytes.len();
+
+    // Prefixes
+    let mut rep_prefix: Option<RepPrefix> = None;
+    let mut segment_prefix: Option<u8> = None;
+    let mut lock_prefix = false;
+    let mut prefix_length: usize = 0;
+    while curr_byte + prefix_length < bytes_len {
+        let prefix_byte = bytes[curr_byte + prefix_length];
+        match prefix_byte {
+            op_code::prefix::REP => rep_prefix = Some(RepPrefix::Rep),
+            op_code::prefix::REPNE => rep_prefix = Some(RepPrefix::Repne),
+            op_code::prefix::LOCK => lock_prefix = true,
+            op_code::prefix::SEGMENT_ES
+            | op_code::prefix::SEGMENT_CS
+            | op_code::prefix::SEGMENT_SS
+            | op_code::prefix::SEGMENT_DS => {
+                segment_prefix = Some((prefix_byte & 0b0001_1000) >> 3)
+            }
+            _ => break,
+        }
+        prefix_length += 1;
+    }
+
+    let op_byte = curr_byte + prefix_length; // First byte after the prefixes
+    if op_byte >= bytes_len {
+        eprintln!(
+            "Error: truncated instruction at offset {:04X}, prefix without instruction",
+            curr_byte
+        );
+        return Err(());
+    }
+
+    let decoded = INSTRUCTION_TABLE
+        .iter()
+        .find_map(|encoding| decode_encoding(encoding, bytes, op_byte, rep_prefix.is_some()));
+
+    let (mut instruction_length, mut instruction) = match decoded {
+        Some(decoded) => decoded,
+        None => {
+            if is_truncated(bytes, op_byte) {
+                eprintln!("Error: truncated instruction at offset {:04X}", curr_byte);
+            } else {
+                eprintln!(
+                    "Error: instruction not handled (byte: {:#b})",
+                    bytes[op_byte]
+                );
+            }
+            return Err(());
+        }
+    };
+
+    if prefix_length > 0 {
+        instruction_length += prefix_length;
+        instruction.start_byte = curr_byte;
+        instruction.length += prefix_length;
+        instruction.rep_prefix = rep_prefix;
+
+        // Increments are relative to the start of the instruction, which is now the first prefix.
+        for operand in [
+            instruction.dest_operand.as_mut(),
+            instruction.src_operand.as_mut(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if operand.format == OperandFormat::Relative {
+                operand.literal = operand
+                    .literal
+                    .map(|literal| literal.wrapping_add(prefix_length as u16));
+            }
+        }
+
+        if let Some(segment) = segment_prefix {
+            apply_segment_override(&mut instruction, segment);
+        }
+
+        // LOCK only asserts the bus lock signal, so it doesn't change what is simulated.
+        if lock_prefix {
+            instruction.lock_prefix = true;
+            if let Some(time_estimation) = instruction.time_estimation.as_mut() {
+                time_estimation.cycles_base += 2;
+            }
+        }
+    }
+
+    Ok((instruction_length, instruction))
+}
+
+/// Whether the instruction at `op_byte` only failed to decode because the bytes end before it
+/// does, found by decoding it again with the missing bytes as zeroes.
+fn is_truncated(bytes: &[u8], op_byte: usize) -> bool {
+    const MAX_INSTRUCTION_LENGTH: usize = 6;
+
+    let mut padded = bytes[op_byte..].to_vec();
+    padded.resize(padded.len() + MAX_INSTRUCTION_LENGTH, 0);
+    INSTRUCTION_TABLE
+        .iter()
+        .any(|encoding| decode_encoding(encoding, &padded, 0, false).is_some())
+}
+
+/// Values of the fields read from an instruction encoding.
+#[derive(Default)]
+struct FieldValues {
+    d: bool,
+    w: bool,
+    s: bool,
+    v: bool,
+    mode: u8,
+    reg: u8,
+    rm: u8,
+    sr: u8,
+    esc: u8,
+    disp_lo: u8,
+    disp_hi: u8,
+    data: u16,
+    /// Whether `data` is a word, either encoded as one or sign extended from a byte.
+    data_word: bool,
+    /// Whether `data` was sign extended from a byte.
+    data_signed: bool,
+    ip_inc: i16,
+    ip_inc_word: bool,
+    address: u16,
+    far_segment: u16,
+}
+
+/// Reads the fields of an instruction, with bit fields taken from the most significant bit of each byte.
+struct FieldReader<'a> {
+    bytes: &'a [u8],
+    current: usize,
+    length: usize,
+    byte: u8,
+    bits_left: u8,
+}
+
+impl FieldReader<'_> {
+    fn read_bits(&mut self, count: u8) -> Option<u8> {
+        if self.bits_left == 0 {
+            self.byte = self.read_byte()?;
+            self.bits_left = 8;
+        }
+
+        self.bits_left -= count;
+        Some(((self.byte as u16 >> self.bits_left) & ((1 << count) - 1)) as u8)
+    }
+
+    fn read_byte(&mut self) -> Option<u8> {
+        let byte = *self.bytes.get(self.current + self.length)?;
+        self.length += 1;
+        Some(byte)
+    }
+
+    fn read_word(&mut self) -> Option<u16> {
+        Some(u16::from_le_bytes([self.read_byte()?, self.read_byte()?]))
+    }
+}
+
+/// Decodes the instruction starting at `current` as `encoding`.
+/// Returns `None` if the fixed bits of the encoding don't match or its operands aren't valid.
+/// `repeat` is whether the instruction has a repeat prefix, used to time string instructions.
+/// Returns instruction length in bytes and the decoded instruction.
+fn decode_encoding(
+    encoding: &Encoding,
+    bytes: &[u8],
+    current: usize,
+    repeat: bool,
+) -> Option<(usize, Instruction)> {
+    let mut reader = FieldReader {
+        bytes,
+        current,
+        length: 0,
+        byte: 0,
+        bits_left: 0,
+    };
+    let mut values = FieldValues::default();
+
+    for field in encoding.fields {
+        match *field {
+            Field::Bits(count, bits) => {
+                if reader.read_bits(count)? != bits {
+                    return None;
+                }
+            }
+            Field::D => values.d = reader.read_bits(1)? != 0,
+            Field::W => values.w = reader.read_bits(1)? != 0,
+            Field::S => values.s = reader.read_bits(1)? != 0,
+            Field::V => values.v = reader.read_bits(1)? != 0,
+            Field::Mod => values.mode = reader.read_bits(2)?,
+            Field::Reg => values.reg = reader.read_bits(3)?,
+            Field::Rm => values.rm = reader.read_bits(3)?,
+            Field::Sr => values.sr = reader.read_bits(2)?,
+            Field::Esc => values.esc = values.esc << 3 | reader.read_bits(3)?,
+            Field::ImplicitW(word) => values.w = word,
+            Field::Disp => match values.mode {
+                displacement_mode::MEM_8_BIT => values.disp_lo = reader.read_byte()?,
+                displacement_mode::MEM_16_BIT => {
+                    [values.disp_lo, values.disp_hi] = reader.read_word()?.to_le_bytes()
+                }
+                displacement_mode::MEM_0_BIT if values.rm == 0b110 => {
+                    [values.disp_lo, values.disp_hi] = reader.read_word()?.to_le_bytes()
+                }
+                _ => {}
+            },
+            Field::Data => {
+                if values.w && !values.s {
+                    values.data = reader.read_word()?;
+                } else if values.w {
+                    // s:w = 11 carries a byte that is sign extended to a word.
+                    values.data = reader.read_byte()? as i8 as u16;
+                    values.data_signed = true;
+                } else {
+                    values.data = reader.read_byte()? as u16;
+                }
+                values.data_word = values.w;
+            }
+            Field::Data8 => values.data = reader.read_byte()? as u16,
+            Field::Data16 => {
+                values.data = reader.read_word()?;
+                values.data_word = true;
+            }
+            Field::IpInc8 => values.ip_inc = reader.read_byte()? as i8 as i16,
+            Field::IpInc16 => {
+                values.ip_inc = reader.read_word()? as i16;
+                values.ip_inc_word = true;
+            }
+            Field::Addr => values.address = reader.read_word()?,
+            Field::FarAddr => {
+                values.address = reader.read_word()?;
+                values.far_segment = reader.read_word()?;
+            }
+        }
+    }
+
+    let op = encoding.op_code;
+    let length = reader.length;
+
+    let mut operands = Vec::with_capacity(encoding.operands.len());
+    for operand in encoding.operands {
+        operands.push(decode_operand(*operand, op, &values, length)?);
+    }
+
+    // d == 1 => reg is destination
+    if values.d {
+        operands.swap(0, 1);
+    }
+
+    let dest_operand = operands.first().copied();
+    let src_operand = operands.get(1).copied();
+
+    let time_estimation = if is_string_instruction(op) {
+        InstructionTime::new_for_string(op, repeat)
+    } else {
+        InstructionTime::new_from_estimation(op, dest_operand.as_ref(), src_operand.as_ref())
+    };
+
+    let instruction = Instruction::new(
+        op,
+        dest_operand,
+        src_operand,
+        current,
+        length,
+        time_estimation,
+    );
+
+    Some((length, instruction))
+}
+
+/// Decodes an operand of an instruction from its field values.
+fn decode_operand(
+    operand: Operand,
+    op: OpCode,
+    values: &FieldValues,
+    length: usize,
+) -> Option<InstructionOperand> {
+    let instruction_operand = match operand {
+        Operand::Reg => get_register_operand(values.reg, values.w),
+        Operand::Rm => decode_rm_operand(values)?,
+        Operand::RmSized => {
+            let mut rm_operand = decode_rm_operand(values)?;
+            if values.mode != displacement_mode::REGISTER {
+                rm_operand.format = OperandFormat::Sized;
+            }
+            rm_operand
+        }
+        Operand::Mem if values.mode == displacement_mode::REGISTER => return None,
+        Operand::Mem => decode_rm_operand(values)?,
+        // Far pointers can only be loaded from memory
+        Operand::FarMem if values.mode == displacement_mode::REGISTER => return None,
+        Operand::FarMem => {
+            let mut rm_operand = decode_rm_operand(values)?;
+            rm_operand.format = OperandFormat::Far;
+            rm_operand
+        }
+        Operand::Sr => get_segment_register_operand(values.sr),
+        Operand::Acc => get_register_operand(register::word::AX, values.w),
+        Operand::Dx => get_register_operand(register::word::DX, true),
+        Operand::Count if values.v => get_register_operand(register::byte::CL, false),
+        Operand::Count => get_literal_operand(1, false),
+        Operand::Immediate => {
+            let mut literal_operand = get_literal_operand(values.data, values.data_word);
+            // NASM sign extends a byte whenever it can, unless the word is `strict`. That includes
+            // the accumulator forms, which it would encode as the sign extended form.
+            let sign_extendable =
+                has_sign_extended_form(op) && i8::try_from(values.data as i16).is_ok();
+            literal_operand.format = if matches!(op, OpCode::Aam | OpCode::Aad) {
+                // AAM/AAD only show their number base when it isn't the usual 10.
+                if values.data == 10 {
+                    OperandFormat::Hidden
+                } else {
+                    OperandFormat::Plain
+                }
+            } else if values.data_signed {
+                OperandFormat::Signed
+            } else if values.data_word && sign_extendable {
+                OperandFormat::StrictWord
+            } else {
+                OperandFormat::Plain
+            };
+            literal_operand
+        }
+        Operand::Address => {
+            let [addr_lo, addr_hi] = values.address.to_le_bytes();
+            get_eac_operand(
+                0b110,
+                displacement_mode::MEM_0_BIT,
+                values.w,
+                addr_lo,
+                addr_hi,
+            )?
+        }
+        Operand::IpIncrement => {
+            let increment = values.ip_inc.wrapping_add(length as i16);
+            let mut literal_operand = get_literal_operand(increment as u16, values.ip_inc_word);
+            literal_operand.format = OperandFormat::Relative;
+            literal_operand
+        }
+        Operand::FarOffset => {
+            let mut literal_operand = get_literal_operand(values.address, true);
+            literal_operand.format = OperandFormat::FarAddress;
+            literal_operand
+        }
+        Operand::FarSegment => {
+            let mut literal_operand = get_literal_operand(values.far_segment, true);
+            literal_operand.format = OperandFormat::Hidden;
+            literal_operand
+        }
+        Operand::EscCode => get_literal_operand(values.esc as u16, false),
+        Operand::EscRm if values.mode == displacement_mode::REGISTER => {
+            get_literal_operand(values.rm as u16, false)
+        }
+        Operand::EscRm => decode_rm_operand(values)?,
+        Operand::ImplicitAcc => {
+            let mut acc_operand = get_register_operand(register::word::AX, values.w);
+            acc_operand.format = OperandFormat::Hidden;
+            acc_operand
+        }
+        Operand::ImplicitSi | Operand::ImplicitDi | Operand::ImplicitBx => {
+            let rm = match operand {
+                Operand::ImplicitSi => 0b100,
+                Operand::ImplicitDi => 0b101,
+                _ => 0b111,
+            };
+            let mut eac_operand =
+                get_eac_operand(rm, displacement_mode::MEM_0_BIT, values.w, 0, 0)?;
+            eac_operand.format = OperandFormat::Hidden;
+            eac_operand
+        }
+    };
+
+    Some(instruction_operand)
+}
+
+/// Decodes the register/memory operand selected by the `mod` and `r/m` fields.
+fn decode_rm_operand(values: &FieldValues) -> Option<InstructionOperand> {
+    if values.mode == displacement_mode::REGISTER {
+        Some(get_register_operand(values.rm, values.w))
+    } else {
+        get_eac_operand(
+            values.rm,
+            values.mode,
+            values.w,
+            values.disp_lo,
+            values.disp_hi,
+        )
+    }
+}
+
+fn get_literal_operand(literal: u16, word: bool) -> InstructionOperand {
+    let mut operand = InstructionOperand::new(OperandType::LITERAL);
+    operand.literal = Some(literal);
+    operand.register_word = Some(word);
+    operand
+}
+
+/// Whether `op` has an immediate to register/memory form with a sign extended byte (`s` bit).
+fn has_sign_extended_form(op: OpCode) -> bool {
+    matches!(
+        op,
+        OpCode::Add
+            | OpCode::Adc
+            | OpCode::Sub
+            | OpCode::Sbb
+            | OpCode::Cmp
+            | OpCode::And
+            | OpCode::Or
+            | OpCode::Xor
+    )
+}
+
+fn is_string_instruction(op: OpCode) -> bool {
+    matches!(
+        op,
+        OpCode::Movs | OpCode::Cmps | OpCode::Scas | OpCode::Lods | OpCode::Stos
+    )
+}
+
+/// Applies a segment override prefix to the memory operands of an instruction.
+/// Segment overrides take 2 more EA cycles.
+fn apply_segment_override(instruction: &mut Instruction, segment: u8) {
+    let is_string = is_string_instruction(instruction.op_code);
+    instruction.segment_prefix = Some(segment);
+
+    let mut has_memory_operand = false;
+    for operand in [
+        instruction.dest_operand.as_mut(),
+        instruction.src_operand.as_mut(),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        // String instructions always address their destination with ES:DI.
+        let is_string_dest = is_string && operand.eac_reg_0 == Some(register::word::DI);
+        if matches!(operand.operand_type, OperandType::EAC) && !is_string_dest {
+            operand.eac_segment = Some(segment);
+            has_memory_operand = true;
+        }
+    }
+
+    if has_memory_operand {
+        if let Some(time_estimation) = instruction.time_estimation.as_mut() {
+            if time_estimation.cycles_ea > 0 {
+                time_estimation.cycles_ea += 2;
+            }
+        }
+    }
+}
@@ -0,0 +1,281 @@
+use crate::op_code::op::OpCode;
+
+use Field as F;
+use Operand as O;
+
+/// A field of an instruction encoding, as laid out in the manual's encoding chart (Table 4-12).
+/// Bit fields are read from the most significant bit of each byte, the others take whole bytes.
+#[derive(Clone, Copy)]
+pub enum Field {
+    /// Fixed bits: bit count and value.
+    Bits(u8, u8),
+    /// `d` bit: the `reg` field operand is the destination.
+    D,
+    /// `w` bit: word operation.
+    W,
+    /// `s` bit: the immediate byte is sign extended to a word (if `w`).
+    S,
+    /// `v` bit: shift/rotate count is CL instead of 1.
+    V,
+    /// 2 bit `mod` field.
+    Mod,
+    /// 3 bit `reg` field.
+    Reg,
+    /// 3 bit `r/m` field.
+    Rm,
+    /// 2 bit segment register field.
+    Sr,
+    /// 3 bits of the ESC coprocessor op code, which is split in two fields.
+    Esc,
+    /// `w` not encoded in the instruction.
+    ImplicitW(bool),
+    /// Displacement selected by `mod` and `r/m`, if any.
+    Disp,
+    /// Immediate data, a word if `w` and not `s`.
+    Data,
+    /// Immediate byte.
+    Data8,
+    /// Immediate word.
+    Data16,
+    /// 8 bit signed IP increment.
+    IpInc8,
+    /// 16 bit IP increment.
+    IpInc16,
+    /// 16 bit direct address.
+    Addr,
+    /// Far address: 16 bit offset and segment.
+    FarAddr,
+}
+
+/// An operand of a decoded instruction, in destination, source order.
+/// If the encoding has a `d` bit and it's set, the first two operands are swapped.
+#[derive(Clone, Copy)]
+pub enum Operand {
+    /// Register of the `reg` field.
+    Reg,
+    /// Register or memory of the `mod` and `r/m` fields.
+    Rm,
+    /// Like `Rm`, shown with a `byte`/`word` size when it's memory.
+    RmSized,
+    /// Like `Rm`, but it has to be memory.
+    Mem,
+    /// Far pointer in memory, shown with `far`.
+    FarMem,
+    /// Segment register of the `sr` field.
+    Sr,
+    /// AL or AX.
+    Acc,
+    /// DX, as a port.
+    Dx,
+    /// Shift/rotate count: CL if `v`, otherwise 1.
+    Count,
+    /// Immediate data.
+    Immediate,
+    /// Memory at a direct address.
+    Address,
+    /// Jump target relative to the start of the instruction.
+    IpIncrement,
+    /// Offset of a far address, shown as `segment:offset`.
+    FarOffset,
+    /// Segment of a far address, shown with `FarOffset`.
+    FarSegment,
+    /// ESC coprocessor op code.
+    EscCode,
+    /// ESC register/memory operand. Register forms select a coprocessor register, shown as its number.
+    EscRm,
+    /// AL or AX, not shown.
+    ImplicitAcc,
+    /// `[SI]`, not shown.
+    ImplicitSi,
+    /// `[DI]`, not shown.
+    ImplicitDi,
+    /// `[BX]`, not shown.
+    ImplicitBx,
+}
+
+/// An instruction encoding. The first encoding whose fixed bits match is decoded.
+pub struct Encoding {
+    pub op_code: OpCode,
+    pub fields: &'static [Field],
+    pub operands: &'static [Operand],
+}
+
+const fn encoding(
+    op_code: OpCode,
+    fields: &'static [Field],
+    operands: &'static [Operand],
+) -> Encoding {
+    Encoding {
+        op_code,
+        fields,
+        operands,
+    }
+}
+
+#[rustfmt::skip]
+pub static INSTRUCTION_TABLE: &[Encoding] = &[
+    // Data transfer
+    encoding(OpCode::Mov, &[F::Bits(6, 0b100010), F::D, F::W, F::Mod, F::Reg, F::Rm, F::Disp], &[O::Rm, O::Reg]),
+    encoding(OpCode::Mov, &[F::Bits(7, 0b1100011), F::W, F::Mod, F::Bits(3, 0b000), F::Rm, F::Disp, F::Data], &[O::RmSized, O::Immediate]),
+    encoding(OpCode::Mov, &[F::Bits(4, 0b1011), F::W, F::Reg, F::Data], &[O::Reg, O::Immediate]),
+    encoding(OpCode::Mov, &[F::Bits(7, 0b1010000), F::W, F::Addr], &[O::Acc, O::Address]),
+    encoding(OpCode::Mov, &[F::Bits(7, 0b1010001), F::W, F::Addr], &[O::Address, O::Acc]),
+    encoding(OpCode::Mov, &[F::Bits(6, 0b100011), F::D, F::Bits(1, 0), F::Mod, F::Bits(1, 0), F::Sr, F::Rm, F::Disp, F::ImplicitW(true)], &[O::Rm, O::Sr]),
+
+    encoding(OpCode::Push, &[F::Bits(8, 0b11111111), F::Mod, F::Bits(3, 0b110), F::Rm, F::Disp, F::ImplicitW(true)], &[O::RmSized]),
+    encoding(OpCode::Push, &[F::Bits(5, 0b01010), F::Reg, F::ImplicitW(true)], &[O::Reg]),
+    encoding(OpCode::Push, &[F::Bits(3, 0b000), F::Sr, F::Bits(3, 0b110)], &[O::Sr]),
+
+    encoding(OpCode::Pop, &[F::Bits(8, 0b10001111), F::Mod, F::Bits(3, 0b000), F::Rm, F::Disp, F::ImplicitW(true)], &[O::RmSized]),
+    encoding(OpCode::Pop, &[F::Bits(5, 0b01011), F::Reg, F::ImplicitW(true)], &[O::Reg]),
+    encoding(OpCode::Pop, &[F::Bits(3, 0b000), F::Sr, F::Bits(3, 0b111)], &[O::Sr]),
+
+    encoding(OpCode::Xchg, &[F::Bits(7, 0b1000011), F::W, F::Mod, F::Reg, F::Rm, F::Disp], &[O::Reg, O::Rm]),
+    // XCHG AX, AX
+    encoding(OpCode::Nop, &[F::Bits(8, 0b10010000)], &[]),
+    encoding(OpCode::Xchg, &[F::Bits(5, 0b10010), F::Reg, F::ImplicitW(true)], &[O::Acc, O::Reg]),
+
+    encoding(OpCode::In, &[F::Bits(7, 0b1110010), F::W, F::Data8], &[O::Acc, O::Immediate]),
+    encoding(OpCode::In, &[F::Bits(7, 0b1110110), F::W], &[O::Acc, O::Dx]),
+    encoding(OpCode::Out, &[F::Bits(7, 0b1110011), F::W, F::Data8], &[O::Immediate, O::Acc]),
+    encoding(OpCode::Out, &[F::Bits(7, 0b1110111), F::W], &[O::Dx, O::Acc]),
+
+    encoding(OpCode::Xlat, &[F::Bits(8, 0b11010111)], &[O::ImplicitAcc, O::ImplicitBx]),
+    encoding(OpCode::Lea, &[F::Bits(8, 0b10001101), F::Mod, F::Reg, F::Rm, F::Disp, F::ImplicitW(true)], &[O::Reg, O::Mem]),
+    encoding(OpCode::Lds, &[F::Bits(8, 0b11000101), F::Mod, F::Reg, F::Rm, F::Disp, F::ImplicitW(true)], &[O::Reg, O::Mem]),
+    encoding(OpCode::Les, &[F::Bits(8, 0b11000100), F::Mod, F::Reg, F::Rm, F::Disp, F::ImplicitW(true)], &[O::Reg, O::Mem]),
+    encoding(OpCode::Lahf, &[F::Bits(8, 0b10011111)], &[]),
+    encoding(OpCode::Sahf, &[F::Bits(8, 0b10011110)], &[]),
+    encoding(OpCode::Pushf, &[F::Bits(8, 0b10011100)], &[]),
+    encoding(OpCode::Popf, &[F::Bits(8, 0b10011101)], &[]),
+
+    // Arithmetic
+    encoding(OpCode::Add, &[F::Bits(6, 0b000000), F::D, F::W, F::Mod, F::Reg, F::Rm, F::Disp], &[O::Rm, O::Reg]),
+    encoding(OpCode::Add, &[F::Bits(6, 0b100000), F::S, F::W, F::Mod, F::Bits(3, 0b000), F::Rm, F::Disp, F::Data], &[O::RmSized, O::Immediate]),
+    encoding(OpCode::Add, &[F::Bits(7, 0b0000010), F::W, F::Data], &[O::Acc, O::Immediate]),
+
+    encoding(OpCode::Adc, &[F::Bits(6, 0b000100), F::D, F::W, F::Mod, F::Reg, F::Rm, F::Disp], &[O::Rm, O::Reg]),
+    encoding(OpCode::Adc, &[F::Bits(6, 0b100000), F::S, F::W, F::Mod, F::Bits(3, 0b010), F::Rm, F::Disp, F::Data], &[O::RmSized, O::Immediate]),
+    encoding(OpCode::Adc, &[F::Bits(7, 0b0001010), F::W, F::Data], &[O::Acc, O::Immediate]),
+
+    encoding(OpCode::Inc, &[F::Bits(7, 0b1111111), F::W, F::Mod, F::Bits(3, 0b000), F::Rm, F::Disp], &[O::RmSized]),
+    encoding(OpCode::Inc, &[F::Bits(5, 0b01000), F::Reg, F::ImplicitW(true)], &[O::Reg]),
+
+    encoding(OpCode::Aaa, &[F::Bits(8, 0b00110111)], &[]),
+    encoding(OpCode::Daa, &[F::Bits(8, 0b00100111)], &[]),
+
+    encoding(OpCode::Sub, &[F::Bits(6, 0b001010), F::D, F::W, F::Mod, F::Reg, F::Rm, F::Disp], &[O::Rm, O::Reg]),
+    encoding(OpCode::Sub, &[F::Bits(6, 0b100000), F::S, F::W, F::Mod, F::Bits(3, 0b101), F::Rm, F::Disp, F::Data], &[O::RmSized, O::Immediate]),
+    encoding(OpCode::Sub, &[F::Bits(7, 0b0010110), F::W, F::Data], &[O::Acc, O::Immediate]),
+
+    encoding(OpCode::Sbb, &[F::Bits(6, 0b000110), F::D, F::W, F::Mod, F::Reg, F::Rm, F::Disp], &[O::Rm, O::Reg]),
+    encoding(OpCode::Sbb, &[F::Bits(6, 0b100000), F::S, F::W, F::Mod, F::Bits(3, 0b011), F::Rm, F::Disp, F::Data], &[O::RmSized, O::Immediate]),
+    encoding(OpCode::Sbb, &[F::Bits(7, 0b0001110), F::W, F::Data], &[O::Acc, O::Immediate]),
+
+    encoding(OpCode::Dec, &[F::Bits(7, 0b1111111), F::W, F::Mod, F::Bits(3, 0b001), F::Rm, F::Disp], &[O::RmSized]),
+    encoding(OpCode::Dec, &[F::Bits(5, 0b01001), F::Reg, F::ImplicitW(true)], &[O::Reg]),
+    encoding(OpCode::Neg, &[F::Bits(7, 0b1111011), F::W, F::Mod, F::Bits(3, 0b011), F::Rm, F::Disp], &[O::RmSized]),
+
+    encoding(OpCode::Cmp, &[F::Bits(6, 0b001110), F::D, F::W, F::Mod, F::Reg, F::Rm, F::Disp], &[O::Rm, O::Reg]),
+    encoding(OpCode::Cmp, &[F::Bits(6, 0b100000), F::S, F::W, F::Mod, F::Bits(3, 0b111), F::Rm, F::Disp, F::Data], &[O::RmSized, O::Immediate]),
+    encoding(OpCode::Cmp, &[F::Bits(7, 0b0011110), F::W, F::Data], &[O::Acc, O::Immediate]),
+
+    encoding(OpCode::Aas, &[F::Bits(8, 0b00111111)], &[]),
+    encoding(OpCode::Das, &[F::Bits(8, 0b00101111)], &[]),
+    encoding(OpCode::Mul, &[F::Bits(7, 0b1111011), F::W, F::Mod, F::Bits(3, 0b100), F::Rm, F::Disp], &[O::RmSized]),
+    encoding(OpCode::Imul, &[F::Bits(7, 0b1111011), F::W, F::Mod, F::Bits(3, 0b101), F::Rm, F::Disp], &[O::RmSized]),
+    encoding(OpCode::Aam, &[F::Bits(8, 0b11010100), F::Data8], &[O::Immediate]),
+    encoding(OpCode::Div, &[F::Bits(7, 0b1111011), F::W, F::Mod, F::Bits(3, 0b110), F::Rm, F::Disp], &[O::RmSized]),
+    encoding(OpCode::Idiv, &[F::Bits(7, 0b1111011), F::W, F::Mod, F::Bits(3, 0b111), F::Rm, F::Disp], &[O::RmSized]),
+    encoding(OpCode::Aad, &[F::Bits(8, 0b11010101), F::Data8], &[O::Immediate]),
+    encoding(OpCode::Cbw, &[F::Bits(8, 0b10011000)], &[]),
+    encoding(OpCode::Cwd, &[F::Bits(8, 0b10011001)], &[]),
+
+    // Logic
+    encoding(OpCode::Not, &[F::Bits(7, 0b1111011), F::W, F::Mod, F::Bits(3, 0b010), F::Rm, F::Disp], &[O::RmSized]),
+    encoding(OpCode::Shl, &[F::Bits(6, 0b110100), F::V, F::W, F::Mod, F::Bits(3, 0b100), F::Rm, F::Disp], &[O::RmSized, O::Count]),
+    encoding(OpCode::Shr, &[F::Bits(6, 0b110100), F::V, F::W, F::Mod, F::Bits(3, 0b101), F::Rm, F::Disp], &[O::RmSized, O::Count]),
+    encoding(OpCode::Sar, &[F::Bits(6, 0b110100), F::V, F::W, F::Mod, F::Bits(3, 0b111), F::Rm, F::Disp], &[O::RmSized, O::Count]),
+    encoding(OpCode::Rol, &[F::Bits(6, 0b110100), F::V, F::W, F::Mod, F::Bits(3, 0b000), F::Rm, F::Disp], &[O::RmSized, O::Count]),
+    encoding(OpCode::Ror, &[F::Bits(6, 0b110100), F::V, F::W, F::Mod, F::Bits(3, 0b001), F::Rm, F::Disp], &[O::RmSized, O::Count]),
+    encoding(OpCode::Rcl, &[F::Bits(6, 0b110100), F::V, F::W, F::Mod, F::Bits(3, 0b010), F::Rm, F::Disp], &[O::RmSized, O::Count]),
+    encoding(OpCode::Rcr, &[F::Bits(6, 0b110100), F::V, F::W, F::Mod, F::Bits(3, 0b011), F::Rm, F::Disp], &[O::RmSized, O::Count]),
+
+    encoding(OpCode::And, &[F::Bits(6, 0b001000), F::D, F::W, F::Mod, F::Reg, F::Rm, F::Disp], &[O::Rm, O::Reg]),
+    encoding(OpCode::And, &[F::Bits(6, 0b100000), F::S, F::W, F::Mod, F::Bits(3, 0b100), F::Rm, F::Disp, F::Data], &[O::RmSized, O::Immediate]),
+    encoding(OpCode::And, &[F::Bits(7, 0b0010010), F::W, F::Data], &[O::Acc, O::Immediate]),
+
+    encoding(OpCode::Test, &[F::Bits(7, 0b1000010), F::W, F::Mod, F::Reg, F::Rm, F::Disp], &[O::Rm, O::Reg]),
+    encoding(OpCode::Test, &[F::Bits(7, 0b1111011), F::W, F::Mod, F::Bits(3, 0b000), F::Rm, F::Disp, F::Data], &[O::RmSized, O::Immediate]),
+    encoding(OpCode::Test, &[F::Bits(7, 0b1010100), F::W, F::Data], &[O::Acc, O::Immediate]),
+
+    encoding(OpCode::Or, &[F::Bits(6, 0b000010), F::D, F::W, F::Mod, F::Reg, F::Rm, F::Disp], &[O::Rm, O::Reg]),
+    encoding(OpCode::Or, &[F::Bits(6, 0b100000), F::S, F::W, F::Mod, F::Bits(3, 0b001), F::Rm, F::Disp, F::Data], &[O::RmSized, O::Immediate]),
+    encoding(OpCode::Or, &[F::Bits(7, 0b0000110), F::W, F::Data], &[O::Acc, O::Immediate]),
+
+    encoding(OpCode::Xor, &[F::Bits(6, 0b001100), F::D, F::W, F::Mod, F::Reg, F::Rm, F::Disp], &[O::Rm, O::Reg]),
+    encoding(OpCode::Xor, &[F::Bits(6, 0b100000), F::S, F::W, F::Mod, F::Bits(3, 0b110), F::Rm, F::Disp, F::Data], &[O::RmSized, O::Immediate]),
+    encoding(OpCode::Xor, &[F::Bits(7, 0b0011010), F::W, F::Data], &[O::Acc, O::Immediate]),
+
+    // String manipulation
+    encoding(OpCode::Movs, &[F::Bits(7, 0b1010010), F::W], &[O::ImplicitDi, O::ImplicitSi]),
+    encoding(OpCode::Cmps, &[F::Bits(7, 0b1010011), F::W], &[O::ImplicitSi, O::ImplicitDi]),
+    encoding(OpCode::Scas, &[F::Bits(7, 0b1010111), F::W], &[O::ImplicitAcc, O::ImplicitDi]),
+    encoding(OpCode::Lods, &[F::Bits(7, 0b1010110), F::W], &[O::ImplicitAcc, O::ImplicitSi]),
+    encoding(OpCode::Stos, &[F::Bits(7, 0b1010101), F::W], &[O::ImplicitDi, O::ImplicitAcc]),
+
+    // Control transfer
+    encoding(OpCode::Call, &[F::Bits(8, 0b11101000), F::IpInc16], &[O::IpIncrement]),
+    encoding(OpCode::Call, &[F::Bits(8, 0b11111111), F::Mod, F::Bits(3, 0b010), F::Rm, F::Disp, F::ImplicitW(true)], &[O::RmSized]),
+    encoding(OpCode::CallFar, &[F::Bits(8, 0b10011010), F::FarAddr], &[O::FarOffset, O::FarSegment]),
+    encoding(OpCode::CallFar, &[F::Bits(8, 0b11111111), F::Mod, F::Bits(3, 0b011), F::Rm, F::Disp, F::ImplicitW(true)], &[O::FarMem]),
+
+    encoding(OpCode::Jmp, &[F::Bits(8, 0b11101001), F::IpInc16], &[O::IpIncrement]),
+    encoding(OpCode::Jmp, &[F::Bits(8, 0b11101011), F::IpInc8], &[O::IpIncrement]),
+    encoding(OpCode::Jmp, &[F::Bits(8, 0b11111111), F::Mod, F::Bits(3, 0b100), F::Rm, F::Disp, F::ImplicitW(true)], &[O::RmSized]),
+    encoding(OpCode::JmpFar, &[F::Bits(8, 0b11101010), F::FarAddr], &[O::FarOffset, O::FarSegment]),
+    encoding(OpCode::JmpFar, &[F::Bits(8, 0b11111111), F::Mod, F::Bits(3, 0b101), F::Rm, F::Disp, F::ImplicitW(true)], &[O::FarMem]),
+
+    encoding(OpCode::Ret, &[F::Bits(8, 0b11000011)], &[]),
+    encoding(OpCode::Ret, &[F::Bits(8, 0b11000010), F::Data16], &[O::Immediate]),
+    encoding(OpCode::Retf, &[F::Bits(8, 0b11001011)], &[]),
+    encoding(OpCode::Retf, &[F::Bits(8, 0b11001010), F::Data16], &[O::Immediate]),
+
+    encoding(OpCode::Je, &[F::Bits(8, 0b01110100), F::IpInc8], &[O::IpIncrement]),
+    encoding(OpCode::Jl, &[F::Bits(8, 0b01111100), F::IpInc8], &[O::IpIncrement]),
+    encoding(OpCode::Jle, &[F::Bits(8, 0b01111110), F::IpInc8], &[O::IpIncrement]),
+    encoding(OpCode::Jb, &[F::Bits(8, 0b01110010), F::IpInc8], &[O::IpIncrement]),
+    encoding(OpCode::Jbe, &[F::Bits(8, 0b01110110), F::IpInc8], &[O::IpIncrement]),
+    encoding(OpCode::Jp, &[F::Bits(8, 0b01111010), F::IpInc8], &[O::IpIncrement]),
+    encoding(OpCode::Jo, &[F::Bits(8, 0b01110000), F::IpInc8], &[O::IpIncrement]),
+    encoding(OpCode::Js, &[F::Bits(8, 0b01111000), F::IpInc8], &[O::IpIncrement]),
+    encoding(OpCode::Jnz, &[F::Bits(8, 0b01110101), F::IpInc8], &[O::IpIncrement]),
+    encoding(OpCode::Jnl, &[F::Bits(8, 0b01111101), F::IpInc8], &[O::IpIncrement]),
+    encoding(OpCode::Jg, &[F::Bits(8, 0b01111111), F::IpInc8], &[O::IpIncrement]),
+    encoding(OpCode::Jnb, &[F::Bits(8, 0b01110011), F::IpInc8], &[O::IpIncrement]),
+    encoding(OpCode::Ja, &[F::Bits(8, 0b01110111), F::IpInc8], &[O::IpIncrement]),
+    encoding(OpCode::Jnp, &[F::Bits(8, 0b01111011), F::IpInc8], &[O::IpIncrement]),
+    encoding(OpCode::Jno, &[F::Bits(8, 0b01110001), F::IpInc8], &[O::IpIncrement]),
+    encoding(OpCode::Jns, &[F::Bits(8, 0b01111001), F::IpInc8], &[O::IpIncrement]),
+    encoding(OpCode::Loop, &[F::Bits(8, 0b11100010), F::IpInc8], &[O::IpIncrement]),
+    encoding(OpCode::Loopz, &[F::Bits(8, 0b11100001), F::IpInc8], &[O::IpIncrement]),
+    encoding(OpCode::Loopnz, &[F::Bits(8, 0b11100000), F::IpInc8], &[O::IpIncrement]),
+    encoding(OpCode::Jcxz, &[F::Bits(8, 0b11100011), F::IpInc8], &[O::IpIncrement]),
+
+    encoding(OpCode::Int, &[F::Bits(8, 0b11001101), F::Data8], &[O::Immediate]),
+    encoding(OpCode::Int3, &[F::Bits(8, 0b11001100)], &[]),
+    encoding(OpCode::Into, &[F::Bits(8, 0b11001110)], &[]),
+    encoding(OpCode::Iret, &[F::Bits(8, 0b11001111)], &[]),
+
+    // Processor control
+    encoding(OpCode::Clc, &[F::Bits(8, 0b11111000)], &[]),
+    encoding(OpCode::Cmc, &[F::Bits(8, 0b11110101)], &[]),
+    encoding(OpCode::Stc, &[F::Bits(8, 0b11111001)], &[]),
+    encoding(OpCode::Cld, &[F::Bits(8, 0b11111100)], &[]),
+    encoding(OpCode::Std, &[F::Bits(8, 0b11111101)], &[]),
+    encoding(OpCode::Cli, &[F::Bits(8, 0b11111010)], &[]),
+    encoding(OpCode::Sti, &[F::Bits(8, 0b11111011)], &[]),
+    encoding(OpCode::Hlt, &[F::Bits(8, 0b11110100)], &[]),
+    encoding(OpCode::Wait, &[F::Bits(8, 0b10011011)], &[]),
+    encoding(OpCode::Esc, &[F::Bits(5, 0b11011), F::Esc, F::Mod, F::Esc, F::Rm, F::Disp, F::ImplicitW(true)], &[O::EscCode, O::EscRm]),
+];
@@ -8,6 +8,9 @@ mod simulator;
 
 use std::env;
 
+use decoder::formatter::{FormatOptions, Syntax};
+use program::instruction::TimingPolicy;
+
 fn main() -> Result<(), ()> {
     let args: Vec<String> = env::args().collect();
     let args_len = args.len();
@@ -23,11 +26,43 @@ fn main() -> Result<(), ()> {
     // Parse options
     let mut option_dump: bool = false;
     let mut option_time: bool = false;
+    let mut option_dos: bool = false;
+    let mut option_cache: bool = false;
+    let mut option_labels: bool = false;
+    let mut option_listing: bool = false;
+    let mut option_timing_policy = TimingPolicy::Operand;
+    let mut option_syntax = Syntax::Nasm;
+    let mut option_uppercase: Option<bool> = None;
+    let mut option_hex: bool = false;
+    let mut option_resilient: bool = false;
     if args_len > 3 {
         for i in 1..(args_len - 2) {
             match args[i].as_str() {
                 "dump" => option_dump = true,
                 "time" => option_time = true,
+                "dos" => option_dos = true,
+                "cache" => option_cache = true,
+                "labels" => option_labels = true,
+                "listing" => option_listing = true,
+                "nasm" => option_syntax = Syntax::Nasm,
+                "masm" => option_syntax = Syntax::Masm,
+                "att" => option_syntax = Syntax::Att,
+                "upper" => option_uppercase = Some(true),
+                "lower" => option_uppercase = Some(false),
+                "hex" => option_hex = true,
+                "resilient" => option_resilient = true,
+                "time-min" => {
+                    option_time = true;
+                    option_timing_policy = TimingPolicy::Min;
+                }
+                "time-max" => {
+                    option_time = true;
+                    option_timing_policy = TimingPolicy::Max;
+                }
+                "time-operand" => {
+                    option_time = true;
+                    option_timing_policy = TimingPolicy::Operand;
+                }
                 invalid_option_str => println!("Skipping invalid option: {invalid_option_str}"),
             }
         }
@@ -37,10 +72,32 @@ fn main() -> Result<(), ()> {
     let operation = &args[args_len - 2];
     match operation.as_str() {
         "decode" => {
-            decoder::decode(operand, true, option_time)?;
+            // AT&T syntax is usually written in lower case.
+            let format_options = FormatOptions {
+                syntax: option_syntax,
+                uppercase: option_uppercase.unwrap_or(option_syntax != Syntax::Att),
+                hex: option_hex,
+            };
+            decoder::decode(
+                operand,
+                true,
+                option_labels,
+                option_listing,
+                option_time,
+                format_options,
+                option_resilient,
+            )?;
         }
         "simulate" => {
-            simulator::simulate::simulate(operand, option_dump, option_time);
+            simulator::simulate::simulate(
+                operand,
+                option_dump,
+                option_time,
+                option_timing_policy,
+                option_dos,
+                option_cache,
+                Box::new(simulator::io_bus::LoggingIoBus),
+            );
         }
         &_ => {
             print_help();
@@ -55,6 +112,30 @@ fn print_help() {
     println!("\nOptions:");
     println!("  dump:       if simulating, dumps memory into file \"memory.data\". ");
     println!("  time:       if simulating, estimates the cycles the program execution will take.");
+    println!("              In a listing, shows the estimation of each instruction.");
+    println!("  time-min:   same as time, using the minimum time for instructions with a range.");
+    println!("  time-max:   same as time, using the maximum time for instructions with a range.");
+    println!(
+        "  time-operand: same as time, scaling ranged times with the operand values (default)."
+    );
+    println!("  dos:        if simulating, stubs DOS print and exit services (INT 20h/21h).");
+    println!(
+        "  cache:      if simulating, caches decoded instructions until their memory is written."
+    );
+    println!("  labels:     if decoding, outputs labels for branch targets instead of offsets.");
+    println!(
+        "  listing:    if decoding, outputs the offset and bytes of each instruction, and its"
+    );
+    println!("              cycles estimation with time.");
+    println!("  nasm:       if decoding, outputs NASM syntax (default).");
+    println!("  masm:       if decoding, outputs MASM/TASM syntax.");
+    println!("  att:        if decoding, outputs GNU assembler AT&T syntax.");
+    println!("  upper:      if decoding, outputs mnemonics and registers in upper case (default,");
+    println!("              except for att).");
+    println!("  lower:      if decoding, outputs mnemonics and registers in lower case.");
+    println!("  hex:        if decoding, outputs numbers in hex instead of decimal.");
+    println!("  resilient:  if decoding, outputs bytes that can't be decoded as data and keeps");
+    println!("              decoding after them.");
     println!("\nOperations:");
     println!("  decode:     decodes the program and outputs the instruction.");
     println!("  simulate:   decodes and then simulates the program execution.");
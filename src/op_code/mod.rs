@@ -1,9 +1,3 @@
 pub mod op;
+pub mod prefix;
 pub mod strings;
-
-pub mod width_4;
-pub mod width_6;
-pub mod width_7;
-pub mod width_8;
-
-pub mod immediate_reg_mem;
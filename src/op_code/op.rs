@@ -1,12 +1,41 @@
 #[derive(Clone, Copy, PartialEq)]
 pub enum OpCode {
-    Invalid,
-    InvalidAddress,
     EndOfProgram,
     Mov,
     Add,
+    Adc,
     Sub,
+    Sbb,
     Cmp,
+    Inc,
+    Dec,
+    Neg,
+    And,
+    Or,
+    Xor,
+    Test,
+    Not,
+    Mul,
+    Imul,
+    Div,
+    Idiv,
+    Shl,
+    Shr,
+    Sar,
+    Rol,
+    Ror,
+    Rcl,
+    Rcr,
+    Call,
+    CallFar,
+    Jmp,
+    JmpFar,
+    Ret,
+    Retf,
+    Int,
+    Int3,
+    Into,
+    Iret,
     Jnz,
     Je,
     Jl,
@@ -27,4 +56,41 @@ pub enum OpCode {
     Loopz,
     Loopnz,
     Jcxz,
+    Movs,
+    Cmps,
+    Scas,
+    Lods,
+    Stos,
+    Push,
+    Pop,
+    Xchg,
+    Nop,
+    Xlat,
+    Lea,
+    Lds,
+    Les,
+    Lahf,
+    Sahf,
+    Pushf,
+    Popf,
+    In,
+    Out,
+    Daa,
+    Das,
+    Aaa,
+    Aas,
+    Aam,
+    Aad,
+    Cbw,
+    Cwd,
+    Clc,
+    Stc,
+    Cmc,
+    Cld,
+    Std,
+    Cli,
+    Sti,
+    Hlt,
+    Wait,
+    Esc,
 }
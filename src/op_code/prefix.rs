@@ -0,0 +1,7 @@
+pub const LOCK: u8 = 0b11110000;
+pub const REPNE: u8 = 0b11110010;
+pub const REP: u8 = 0b11110011;
+pub const SEGMENT_ES: u8 = 0b00100110;
+pub const SEGMENT_CS: u8 = 0b00101110;
+pub const SEGMENT_SS: u8 = 0b00110110;
+pub const SEGMENT_DS: u8 = 0b00111110;
@@ -1,11 +1,41 @@
 use super::op::OpCode;
+use crate::program::instruction::RepPrefix;
 
 pub fn get_str(op_code: OpCode) -> &'static str {
     match op_code {
         OpCode::Mov => "MOV",
         OpCode::Add => "ADD",
+        OpCode::Adc => "ADC",
         OpCode::Sub => "SUB",
+        OpCode::Sbb => "SBB",
         OpCode::Cmp => "CMP",
+        OpCode::Inc => "INC",
+        OpCode::Dec => "DEC",
+        OpCode::Neg => "NEG",
+        OpCode::And => "AND",
+        OpCode::Or => "OR",
+        OpCode::Xor => "XOR",
+        OpCode::Test => "TEST",
+        OpCode::Not => "NOT",
+        OpCode::Mul => "MUL",
+        OpCode::Imul => "IMUL",
+        OpCode::Div => "DIV",
+        OpCode::Idiv => "IDIV",
+        OpCode::Shl => "SHL",
+        OpCode::Shr => "SHR",
+        OpCode::Sar => "SAR",
+        OpCode::Rol => "ROL",
+        OpCode::Ror => "ROR",
+        OpCode::Rcl => "RCL",
+        OpCode::Rcr => "RCR",
+        OpCode::Call | OpCode::CallFar => "CALL",
+        OpCode::Jmp | OpCode::JmpFar => "JMP",
+        OpCode::Ret => "RET",
+        OpCode::Retf => "RETF",
+        OpCode::Int => "INT",
+        OpCode::Int3 => "INT3",
+        OpCode::Into => "INTO",
+        OpCode::Iret => "IRET",
         OpCode::Jnz => "JNZ",
         OpCode::Je => "JE",
         OpCode::Jl => "JL",
@@ -26,8 +56,52 @@ pub fn get_str(op_code: OpCode) -> &'static str {
         OpCode::Loopz => "LOOPZ",
         OpCode::Loopnz => "LOOPNZ",
         OpCode::Jcxz => "JCXZ",
-        OpCode::Invalid => "Invalid OpCode",
-        OpCode::InvalidAddress => "Invalid instruction address",
+        OpCode::Movs => "MOVS",
+        OpCode::Cmps => "CMPS",
+        OpCode::Scas => "SCAS",
+        OpCode::Lods => "LODS",
+        OpCode::Stos => "STOS",
+        OpCode::Push => "PUSH",
+        OpCode::Pop => "POP",
+        OpCode::Xchg => "XCHG",
+        OpCode::Nop => "NOP",
+        OpCode::Xlat => "XLAT",
+        OpCode::Lea => "LEA",
+        OpCode::Lds => "LDS",
+        OpCode::Les => "LES",
+        OpCode::Lahf => "LAHF",
+        OpCode::Sahf => "SAHF",
+        OpCode::Pushf => "PUSHF",
+        OpCode::Popf => "POPF",
+        OpCode::In => "IN",
+        OpCode::Out => "OUT",
+        OpCode::Daa => "DAA",
+        OpCode::Das => "DAS",
+        OpCode::Aaa => "AAA",
+        OpCode::Aas => "AAS",
+        OpCode::Aam => "AAM",
+        OpCode::Aad => "AAD",
+        OpCode::Cbw => "CBW",
+        OpCode::Cwd => "CWD",
+        OpCode::Clc => "CLC",
+        OpCode::Stc => "STC",
+        OpCode::Cmc => "CMC",
+        OpCode::Cld => "CLD",
+        OpCode::Std => "STD",
+        OpCode::Cli => "CLI",
+        OpCode::Sti => "STI",
+        OpCode::Hlt => "HLT",
+        OpCode::Wait => "WAIT",
+        OpCode::Esc => "ESC",
         OpCode::EndOfProgram => "End of program",
     }
 }
+
+/// Returns the repeat prefix string, using the REPE form for the instructions that check ZF.
+pub fn get_rep_prefix_str(rep_prefix: RepPrefix, op_code: OpCode) -> &'static str {
+    match (rep_prefix, op_code) {
+        (RepPrefix::Rep, OpCode::Cmps | OpCode::Scas) => "REPE",
+        (RepPrefix::Rep, _) => "REP",
+        (RepPrefix::Repne, _) => "REPNE",
+    }
+}
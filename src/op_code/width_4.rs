@@ -1 +0,0 @@
-pub const MOV_IMMEDIATE_REG: u8 = 0b1011;
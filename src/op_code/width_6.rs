@@ -1,7 +0,0 @@
-pub const MOV_REG_MEM_REG: u8 = 0b100010;
-
-pub const ADD_REG_MEM_REG: u8 = 0b000000;
-pub const SUB_REG_MEM_REG: u8 = 0b001010;
-pub const CMP_REG_MEM_REG: u8 = 0b001110;
-
-pub const IMMEDIATE_REG_MEM: u8 = 0b100000;
@@ -1,7 +0,0 @@
-pub const MOV_IMMEDIATE_REG_MEM: u8 = 0b1100011;
-pub const MOV_MEM_ACC: u8 = 0b1010000;
-pub const MOV_ACC_MEM: u8 = 0b1010001;
-
-pub const ADD_IMMEDIATE_ACC: u8 = 0b0000010;
-pub const SUB_IMMEDIATE_ACC: u8 = 0b0010110;
-pub const CMP_IMMEDIATE_ACC: u8 = 0b0011110;
@@ -1,20 +0,0 @@
-pub const JNZ: u8 = 0b01110101;
-pub const JE: u8 = 0b01110100;
-pub const JL: u8 = 0b01111100;
-pub const JLE: u8 = 0b01111110;
-pub const JB: u8 = 0b01110010;
-pub const JBE: u8 = 0b01110110;
-pub const JP: u8 = 0b01111010;
-pub const JO: u8 = 0b01110000;
-pub const JS: u8 = 0b01111000;
-pub const JNL: u8 = 0b01111101;
-pub const JG: u8 = 0b01111111;
-pub const JNB: u8 = 0b01110011;
-pub const JA: u8 = 0b01110111;
-pub const JNP: u8 = 0b01111011;
-pub const JNO: u8 = 0b01110001;
-pub const JNS: u8 = 0b01111001;
-pub const LOOP: u8 = 0b11100010;
-pub const LOOPZ: u8 = 0b11100001;
-pub const LOOPNZ: u8 = 0b11100000;
-pub const JCXZ: u8 = 0b11100011;
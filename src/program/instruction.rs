@@ -8,12 +8,15 @@ pub struct Instruction {
     pub dest_operand: Option<InstructionOperand>,
     pub src_operand: Option<InstructionOperand>,
 
-    pub decoded_string: Option<String>,
-
     pub start_byte: usize,
     pub length: usize,
 
     pub time_estimation: Option<InstructionTime>,
+
+    pub rep_prefix: Option<RepPrefix>,
+    /// Segment register from a segment override prefix, also set in the memory operands it applies to.
+    pub segment_prefix: Option<u8>,
+    pub lock_prefix: bool,
 }
 
 impl Instruction {
@@ -21,7 +24,6 @@ impl Instruction {
         op_code: OpCode,
         dest_operand: Option<InstructionOperand>,
         src_operand: Option<InstructionOperand>,
-        decoded_string: Option<String>,
         start_byte: usize,
         length: usize,
         time_estimation: Option<InstructionTime>,
@@ -30,42 +32,37 @@ impl Instruction {
             op_code,
             dest_operand,
             src_operand,
-            decoded_string,
             start_byte,
             length,
             time_estimation,
+            rep_prefix: None,
+            segment_prefix: None,
+            lock_prefix: false,
         }
     }
 }
 
-pub static INVALID: Instruction = Instruction {
-    op_code: OpCode::Invalid,
-    dest_operand: None,
-    src_operand: None,
-    decoded_string: None,
-    start_byte: 0,
-    length: 0,
-    time_estimation: None,
-};
-pub static INVALID_ADDRESS: Instruction = Instruction {
-    op_code: OpCode::InvalidAddress,
-    dest_operand: None,
-    src_operand: None,
-    decoded_string: None,
-    start_byte: 0,
-    length: 0,
-    time_estimation: None,
-};
 pub static END_OF_PROGRAM: Instruction = Instruction {
     op_code: OpCode::EndOfProgram,
     dest_operand: None,
     src_operand: None,
-    decoded_string: None,
     start_byte: 0,
     length: 0,
     time_estimation: None,
+    rep_prefix: None,
+    segment_prefix: None,
+    lock_prefix: false,
 };
 
+/// Repeat prefix for string instructions.
+#[derive(Clone, Copy, PartialEq)]
+pub enum RepPrefix {
+    /// `0xF3`: REP, or REPE/REPZ for CMPS and SCAS.
+    Rep,
+    /// `0xF2`: REPNE/REPNZ.
+    Repne,
+}
+
 #[derive(Clone, Copy)]
 pub struct InstructionOperand {
     pub operand_type: OperandType,
@@ -74,7 +71,12 @@ pub struct InstructionOperand {
     pub eac_reg_0: Option<u8>,
     pub eac_reg_1: Option<u8>,
     pub eac_displacement: Option<u16>,
+    /// Whether the displacement was encoded as a word.
+    pub eac_displacement_word: bool,
+    /// Segment register from a segment override prefix, `None` uses the default segment.
+    pub eac_segment: Option<u8>,
     pub literal: Option<u16>,
+    pub format: OperandFormat,
 }
 
 impl InstructionOperand {
@@ -86,22 +88,62 @@ impl InstructionOperand {
             eac_reg_0: None,
             eac_reg_1: None,
             eac_displacement: None,
+            eac_displacement_word: false,
+            eac_segment: None,
             literal: None,
+            format: OperandFormat::Plain,
         }
     }
 }
 
 #[derive(Clone, Copy)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum OperandType {
     REGISTER,
+    SEGMENT,
     EAC,
     LITERAL,
 }
 
+/// How an operand is shown when disassembled, for what its values alone don't tell.
+#[derive(Clone, Copy, PartialEq)]
+pub enum OperandFormat {
+    Plain,
+    /// Not shown: implicit operands, and the AAM/AAD base when it's the usual 10.
+    Hidden,
+    /// Memory shown with its size, as no other operand gives it.
+    Sized,
+    /// Far pointer in memory.
+    Far,
+    /// Immediate sign extended from a byte.
+    Signed,
+    /// Word immediate that fits in a sign extended byte, so assemblers would shorten it.
+    StrictWord,
+    /// Branch increment, relative to the start of the instruction.
+    Relative,
+    /// Offset of a far address, with the segment in the next operand.
+    FarAddress,
+}
+
 #[derive(Clone, Copy)]
 pub struct InstructionTime {
     pub cycles_base: usize,
     pub cycles_ea: usize,
+    /// Cycles added for each unit of a count only known at run time (e.g. bits shifted by CL).
+    pub cycles_per_count: usize,
+    /// Cycles between the manual's minimum (`cycles_base`) and maximum times.
+    pub cycles_range: usize,
+    /// Cycles added when a conditional transfer is taken (`cycles_base` is the not taken time).
+    pub cycles_taken: usize,
+}
+
+/// How to pick a time for instructions the manual gives as a min-max range (MUL, DIV...).
+#[derive(Clone, Copy, PartialEq)]
+pub enum TimingPolicy {
+    Min,
+    Max,
+    /// Scale the range with the operand values seen while simulating.
+    Operand,
 }
 
 impl InstructionTime {
@@ -109,90 +151,412 @@ impl InstructionTime {
         Self {
             cycles_base,
             cycles_ea,
+            cycles_per_count: 0,
+            cycles_range: 0,
+            cycles_taken: 0,
         }
     }
 
+    pub fn new_with_count(cycles_base: usize, cycles_ea: usize, cycles_per_count: usize) -> Self {
+        Self {
+            cycles_per_count,
+            ..Self::new(cycles_base, cycles_ea)
+        }
+    }
+
+    pub fn new_with_range(cycles_min: usize, cycles_max: usize, cycles_ea: usize) -> Self {
+        Self {
+            cycles_range: cycles_max - cycles_min,
+            ..Self::new(cycles_min, cycles_ea)
+        }
+    }
+
+    pub fn new_with_taken(cycles_taken: usize, cycles_not_taken: usize) -> Self {
+        Self {
+            cycles_taken: cycles_taken - cycles_not_taken,
+            ..Self::new(cycles_not_taken, 0)
+        }
+    }
+
+    /// Total time without the run time dependent cycles (counts and ranges).
     pub fn total_time(&self) -> usize {
         self.cycles_base + self.cycles_ea
     }
 
+    /// Returns the cycles to take from `cycles_range` under `policy`.
+    /// `operand_fraction` is `(numerator, denominator)`, only used by `TimingPolicy::Operand`.
+    pub fn get_range_cycles(
+        &self,
+        policy: TimingPolicy,
+        operand_fraction: (usize, usize),
+    ) -> usize {
+        match policy {
+            TimingPolicy::Min => 0,
+            TimingPolicy::Max => self.cycles_range,
+            TimingPolicy::Operand => self.cycles_range * operand_fraction.0 / operand_fraction.1,
+        }
+    }
+
+    /// Estimates the cycles for a string instruction, with or without a repeat prefix.
+    /// Repeated instructions take `cycles_per_count` for each repetition.
+    pub fn new_for_string(op_code: OpCode, repeat: bool) -> Option<Self> {
+        let (cycles_single, cycles_per_repetition) = match op_code {
+            OpCode::Movs => (18, 17),
+            OpCode::Cmps => (22, 22),
+            OpCode::Scas => (15, 15),
+            OpCode::Lods => (12, 13),
+            OpCode::Stos => (11, 10),
+            _ => return None,
+        };
+
+        if repeat {
+            Some(Self::new_with_count(9, 0, cycles_per_repetition))
+        } else {
+            Some(Self::new(cycles_single, 0))
+        }
+    }
+
+    /// Estimates the cycles for an instruction from its operands.
+    /// Single operand instructions pass their operand as `dest_operand`.
+    /// See table 2.21 in the 8086 Family Users Manual.
     pub fn new_from_estimation(
         op_code: OpCode,
-        dest_operand: &InstructionOperand,
-        src_operand: &InstructionOperand,
+        dest_operand: Option<&InstructionOperand>,
+        src_operand: Option<&InstructionOperand>,
     ) -> Option<Self> {
         use crate::register::word::AX;
         use OperandType::*;
 
         match op_code {
-            OpCode::Invalid => None,
-            OpCode::InvalidAddress => None,
             OpCode::EndOfProgram => None,
 
-            OpCode::Add | OpCode::Sub => {
+            OpCode::Add
+            | OpCode::Adc
+            | OpCode::Sub
+            | OpCode::Sbb
+            | OpCode::And
+            | OpCode::Or
+            | OpCode::Xor => {
+                let (dest_operand, src_operand) = (dest_operand.unwrap(), src_operand.unwrap());
                 match (dest_operand.operand_type, src_operand.operand_type) {
                     (REGISTER, REGISTER) => Some(Self::new(3, 0)),
                     (REGISTER, EAC) => Some(Self::new(
                         9,
-                        Self::get_cycles_for_ea(&dest_operand, &src_operand),
+                        Self::get_cycles_for_ea(dest_operand, src_operand),
                     )),
                     (EAC, REGISTER) => Some(Self::new(
                         16,
-                        Self::get_cycles_for_ea(&dest_operand, &src_operand),
+                        Self::get_cycles_for_ea(dest_operand, src_operand),
                     )),
                     (EAC, LITERAL) => Some(Self::new(
                         17,
-                        Self::get_cycles_for_ea(&dest_operand, &src_operand),
+                        Self::get_cycles_for_ea(dest_operand, src_operand),
                     )),
-                    (REGISTER, LITERAL) if dest_operand.register.unwrap() == AX => {
-                        Some(Self::new(4, 0))
+                    (REGISTER, LITERAL) => Some(Self::new(4, 0)),
+                    _ => panic!("Error: Invalid operands for instruction detected while timing."),
+                }
+            }
+
+            OpCode::Mov => {
+                let (dest_operand, src_operand) = (dest_operand.unwrap(), src_operand.unwrap());
+                match (dest_operand.operand_type, src_operand.operand_type) {
+                    (EAC, REGISTER) if src_operand.register.unwrap() == AX => {
+                        Some(Self::new(10, 0))
+                    }
+                    (REGISTER, EAC) if dest_operand.register.unwrap() == AX => {
+                        Some(Self::new(10, 0))
                     }
+                    (REGISTER, REGISTER) => Some(Self::new(2, 0)),
+                    (REGISTER, EAC) => Some(Self::new(
+                        8,
+                        Self::get_cycles_for_ea(dest_operand, src_operand),
+                    )),
+                    (EAC, REGISTER) => Some(Self::new(
+                        9,
+                        Self::get_cycles_for_ea(dest_operand, src_operand),
+                    )),
                     (REGISTER, LITERAL) => Some(Self::new(4, 0)),
+                    (EAC, LITERAL) => Some(Self::new(
+                        10,
+                        Self::get_cycles_for_ea(dest_operand, src_operand),
+                    )),
+                    (SEGMENT, REGISTER) | (REGISTER, SEGMENT) => Some(Self::new(2, 0)),
+                    (SEGMENT, EAC) => Some(Self::new(
+                        8,
+                        Self::get_cycles_for_ea(dest_operand, src_operand),
+                    )),
+                    (EAC, SEGMENT) => Some(Self::new(
+                        9,
+                        Self::get_cycles_for_ea(dest_operand, src_operand),
+                    )),
                     _ => panic!("Error: Invalid operands for instruction detected while timing."),
                 }
             }
 
-            OpCode::Mov => match (dest_operand.operand_type, src_operand.operand_type) {
-                (EAC, REGISTER) if src_operand.register.unwrap() == AX => Some(Self::new(10, 0)),
-                (REGISTER, EAC) if dest_operand.register.unwrap() == AX => Some(Self::new(10, 0)),
-                (REGISTER, REGISTER) => Some(Self::new(2, 0)),
-                (REGISTER, EAC) => Some(Self::new(
+            OpCode::Push => match dest_operand.unwrap().operand_type {
+                REGISTER => Some(Self::new(11, 0)),
+                SEGMENT => Some(Self::new(10, 0)),
+                EAC => Some(Self::new(
+                    16,
+                    Self::get_operand_ea_cycles(dest_operand.unwrap()),
+                )),
+                LITERAL => panic!("Error: Invalid operands for instruction detected while timing."),
+            },
+
+            OpCode::Pop => match dest_operand.unwrap().operand_type {
+                REGISTER | SEGMENT => Some(Self::new(8, 0)),
+                EAC => Some(Self::new(
+                    17,
+                    Self::get_operand_ea_cycles(dest_operand.unwrap()),
+                )),
+                LITERAL => panic!("Error: Invalid operands for instruction detected while timing."),
+            },
+
+            OpCode::Xchg => {
+                let (dest_operand, src_operand) = (dest_operand.unwrap(), src_operand.unwrap());
+                match (dest_operand.operand_type, src_operand.operand_type) {
+                    (REGISTER, REGISTER)
+                        if dest_operand.register.unwrap() == AX
+                            && dest_operand.register_word.unwrap() =>
+                    {
+                        Some(Self::new(3, 0))
+                    }
+                    (REGISTER, REGISTER) => Some(Self::new(4, 0)),
+                    (REGISTER, EAC) | (EAC, REGISTER) => Some(Self::new(
+                        17,
+                        Self::get_cycles_for_ea(dest_operand, src_operand),
+                    )),
+                    _ => panic!("Error: Invalid operands for instruction detected while timing."),
+                }
+            }
+            OpCode::Nop => Some(Self::new(3, 0)),
+            OpCode::Xlat => Some(Self::new(11, 0)),
+            OpCode::Lea => Some(Self::new(
+                2,
+                Self::get_operand_ea_cycles(src_operand.unwrap()),
+            )),
+            OpCode::Lds | OpCode::Les => Some(Self::new(
+                16,
+                Self::get_operand_ea_cycles(src_operand.unwrap()),
+            )),
+            OpCode::Lahf | OpCode::Sahf => Some(Self::new(4, 0)),
+            OpCode::Pushf => Some(Self::new(10, 0)),
+            OpCode::Popf => Some(Self::new(8, 0)),
+
+            OpCode::Daa | OpCode::Das | OpCode::Aaa | OpCode::Aas => Some(Self::new(4, 0)),
+            OpCode::Aam => Some(Self::new(83, 0)),
+            OpCode::Aad => Some(Self::new(60, 0)),
+            OpCode::Cbw => Some(Self::new(2, 0)),
+            OpCode::Cwd => Some(Self::new(5, 0)),
+
+            OpCode::Clc
+            | OpCode::Stc
+            | OpCode::Cmc
+            | OpCode::Cld
+            | OpCode::Std
+            | OpCode::Cli
+            | OpCode::Sti
+            | OpCode::Hlt => Some(Self::new(2, 0)),
+            OpCode::Wait => Some(Self::new(3, 0)),
+            // The source is the memory operand put on the bus for the coprocessor, if any.
+            OpCode::Esc => match src_operand.unwrap().operand_type {
+                EAC => Some(Self::new(
                     8,
-                    Self::get_cycles_for_ea(&dest_operand, &src_operand),
+                    Self::get_operand_ea_cycles(src_operand.unwrap()),
+                )),
+                _ => Some(Self::new(2, 0)),
+            },
+
+            // Fixed ports are an immediate byte, variable ports are in DX.
+            OpCode::In => match src_operand.unwrap().operand_type {
+                LITERAL => Some(Self::new(10, 0)),
+                _ => Some(Self::new(8, 0)),
+            },
+            OpCode::Out => match dest_operand.unwrap().operand_type {
+                LITERAL => Some(Self::new(10, 0)),
+                _ => Some(Self::new(8, 0)),
+            },
+
+            OpCode::Cmp => {
+                let (dest_operand, src_operand) = (dest_operand.unwrap(), src_operand.unwrap());
+                match (dest_operand.operand_type, src_operand.operand_type) {
+                    (REGISTER, REGISTER) => Some(Self::new(3, 0)),
+                    (REGISTER, EAC) | (EAC, REGISTER) => Some(Self::new(
+                        9,
+                        Self::get_cycles_for_ea(dest_operand, src_operand),
+                    )),
+                    (EAC, LITERAL) => Some(Self::new(
+                        10,
+                        Self::get_cycles_for_ea(dest_operand, src_operand),
+                    )),
+                    (REGISTER, LITERAL) => Some(Self::new(4, 0)),
+                    _ => panic!("Error: Invalid operands for instruction detected while timing."),
+                }
+            }
+
+            OpCode::Test => {
+                let (dest_operand, src_operand) = (dest_operand.unwrap(), src_operand.unwrap());
+                match (dest_operand.operand_type, src_operand.operand_type) {
+                    (REGISTER, REGISTER) => Some(Self::new(3, 0)),
+                    (REGISTER, EAC) | (EAC, REGISTER) => Some(Self::new(
+                        9,
+                        Self::get_cycles_for_ea(dest_operand, src_operand),
+                    )),
+                    (EAC, LITERAL) => Some(Self::new(
+                        11,
+                        Self::get_cycles_for_ea(dest_operand, src_operand),
+                    )),
+                    (REGISTER, LITERAL) if dest_operand.register.unwrap() == AX => {
+                        Some(Self::new(4, 0))
+                    }
+                    (REGISTER, LITERAL) => Some(Self::new(5, 0)),
+                    _ => panic!("Error: Invalid operands for instruction detected while timing."),
+                }
+            }
+
+            OpCode::Shl
+            | OpCode::Shr
+            | OpCode::Sar
+            | OpCode::Rol
+            | OpCode::Ror
+            | OpCode::Rcl
+            | OpCode::Rcr => {
+                let (dest_operand, src_operand) = (dest_operand.unwrap(), src_operand.unwrap());
+                match (dest_operand.operand_type, src_operand.operand_type) {
+                    (REGISTER, LITERAL) => Some(Self::new(2, 0)),
+                    (REGISTER, REGISTER) => Some(Self::new_with_count(8, 0, 4)),
+                    (EAC, LITERAL) => {
+                        Some(Self::new(15, Self::get_operand_ea_cycles(dest_operand)))
+                    }
+                    (EAC, REGISTER) => Some(Self::new_with_count(
+                        20,
+                        Self::get_operand_ea_cycles(dest_operand),
+                        4,
+                    )),
+                    _ => panic!("Error: Invalid operands for instruction detected while timing."),
+                }
+            }
+
+            OpCode::Mul | OpCode::Imul | OpCode::Div | OpCode::Idiv => {
+                let dest_operand = dest_operand.unwrap();
+                let word = dest_operand.register_word.unwrap();
+                let (cycles_min, cycles_max) = match (op_code, word) {
+                    (OpCode::Mul, false) => (70, 77),
+                    (OpCode::Mul, true) => (118, 133),
+                    (OpCode::Imul, false) => (80, 98),
+                    (OpCode::Imul, true) => (128, 154),
+                    (OpCode::Div, false) => (80, 90),
+                    (OpCode::Div, true) => (144, 162),
+                    (OpCode::Idiv, false) => (101, 112),
+                    (OpCode::Idiv, true) => (165, 184),
+                    _ => unreachable!(),
+                };
+
+                // Memory operands take 6 extra cycles on top of the register times.
+                match dest_operand.operand_type {
+                    REGISTER => Some(Self::new_with_range(cycles_min, cycles_max, 0)),
+                    EAC => Some(Self::new_with_range(
+                        cycles_min + 6,
+                        cycles_max + 6,
+                        Self::get_operand_ea_cycles(dest_operand),
+                    )),
+                    _ => panic!("Error: Invalid operands for instruction detected while timing."),
+                }
+            }
+
+            OpCode::Inc | OpCode::Dec => {
+                let dest_operand = dest_operand.unwrap();
+                match dest_operand.operand_type {
+                    REGISTER if dest_operand.register_word.unwrap() => Some(Self::new(2, 0)),
+                    REGISTER => Some(Self::new(3, 0)),
+                    EAC => Some(Self::new(15, Self::get_operand_ea_cycles(dest_operand))),
+                    _ => panic!("Error: Invalid operands for instruction detected while timing."),
+                }
+            }
+
+            OpCode::Movs | OpCode::Cmps | OpCode::Scas | OpCode::Lods | OpCode::Stos => {
+                Self::new_for_string(op_code, false)
+            }
+
+            OpCode::Call => match dest_operand.unwrap().operand_type {
+                LITERAL => Some(Self::new(19, 0)),
+                REGISTER => Some(Self::new(16, 0)),
+                EAC => Some(Self::new(
+                    21,
+                    Self::get_operand_ea_cycles(dest_operand.unwrap()),
+                )),
+                _ => panic!("Error: Invalid operands for instruction detected while timing."),
+            },
+
+            OpCode::CallFar => match dest_operand.unwrap().operand_type {
+                LITERAL => Some(Self::new(28, 0)),
+                EAC => Some(Self::new(
+                    37,
+                    Self::get_operand_ea_cycles(dest_operand.unwrap()),
                 )),
-                (EAC, REGISTER) => Some(Self::new(
-                    9,
-                    Self::get_cycles_for_ea(&dest_operand, &src_operand),
+                _ => panic!("Error: Invalid operands for instruction detected while timing."),
+            },
+
+            OpCode::Jmp => match dest_operand.unwrap().operand_type {
+                LITERAL => Some(Self::new(15, 0)),
+                REGISTER => Some(Self::new(11, 0)),
+                EAC => Some(Self::new(
+                    18,
+                    Self::get_operand_ea_cycles(dest_operand.unwrap()),
                 )),
-                (REGISTER, LITERAL) => Some(Self::new(4, 0)),
-                (EAC, LITERAL) => Some(Self::new(
-                    10,
-                    Self::get_cycles_for_ea(&dest_operand, &src_operand),
+                _ => panic!("Error: Invalid operands for instruction detected while timing."),
+            },
+
+            OpCode::JmpFar => match dest_operand.unwrap().operand_type {
+                LITERAL => Some(Self::new(15, 0)),
+                EAC => Some(Self::new(
+                    24,
+                    Self::get_operand_ea_cycles(dest_operand.unwrap()),
                 )),
                 _ => panic!("Error: Invalid operands for instruction detected while timing."),
             },
 
-            OpCode::Cmp => todo!(),
-            OpCode::Jnz => todo!(),
-            OpCode::Je => todo!(),
-            OpCode::Jl => todo!(),
-            OpCode::Jle => todo!(),
-            OpCode::Jb => todo!(),
-            OpCode::Jbe => todo!(),
-            OpCode::Jp => todo!(),
-            OpCode::Jo => todo!(),
-            OpCode::Js => todo!(),
-            OpCode::Jnl => todo!(),
-            OpCode::Jg => todo!(),
-            OpCode::Jnb => todo!(),
-            OpCode::Ja => todo!(),
-            OpCode::Jnp => todo!(),
-            OpCode::Jno => todo!(),
-            OpCode::Jns => todo!(),
-            OpCode::Loop => todo!(),
-            OpCode::Loopz => todo!(),
-            OpCode::Loopnz => todo!(),
-            OpCode::Jcxz => todo!(),
+            // The operand is the optional immediate added to SP after popping.
+            OpCode::Ret if dest_operand.is_some() => Some(Self::new(12, 0)),
+            OpCode::Ret => Some(Self::new(8, 0)),
+            OpCode::Retf if dest_operand.is_some() => Some(Self::new(17, 0)),
+            OpCode::Retf => Some(Self::new(18, 0)),
+
+            OpCode::Int => Some(Self::new(51, 0)),
+            OpCode::Int3 => Some(Self::new(52, 0)),
+            OpCode::Into => Some(Self::new_with_taken(53, 4)),
+            OpCode::Iret => Some(Self::new(24, 0)),
+
+            OpCode::Not | OpCode::Neg => match dest_operand.unwrap().operand_type {
+                REGISTER => Some(Self::new(3, 0)),
+                EAC => Some(Self::new(
+                    16,
+                    Self::get_operand_ea_cycles(dest_operand.unwrap()),
+                )),
+                _ => panic!("Error: Invalid operands for instruction detected while timing."),
+            },
+
+            // Conditional transfers, timed as not taken plus the extra cycles when taken.
+            OpCode::Jnz
+            | OpCode::Je
+            | OpCode::Jl
+            | OpCode::Jle
+            | OpCode::Jb
+            | OpCode::Jbe
+            | OpCode::Jp
+            | OpCode::Jo
+            | OpCode::Js
+            | OpCode::Jnl
+            | OpCode::Jg
+            | OpCode::Jnb
+            | OpCode::Ja
+            | OpCode::Jnp
+            | OpCode::Jno
+            | OpCode::Jns => Some(Self::new_with_taken(16, 4)),
+            OpCode::Loop => Some(Self::new_with_taken(17, 5)),
+            OpCode::Loopz => Some(Self::new_with_taken(18, 6)),
+            OpCode::Loopnz => Some(Self::new_with_taken(19, 5)),
+            OpCode::Jcxz => Some(Self::new_with_taken(18, 6)),
         }
     }
 
@@ -214,6 +578,7 @@ impl InstructionTime {
     fn get_operand_ea_cycles(operand: &InstructionOperand) -> usize {
         match operand.operand_type {
             OperandType::REGISTER => 0,
+            OperandType::SEGMENT => 0,
             OperandType::LITERAL => 0,
             OperandType::EAC => {
                 use crate::register::word::{BP, BX, DI, SI};
@@ -255,15 +620,48 @@ impl InstructionTime {
     }
 
     pub fn get_string(&self) -> String {
-        if self.cycles_ea > 0 {
-            String::from(format!(
+        let range_string = |cycles: usize| {
+            if self.cycles_range > 0 {
+                format!("{}-{}", cycles, cycles + self.cycles_range)
+            } else {
+                format!("{}", cycles)
+            }
+        };
+
+        let mut string = if self.cycles_ea > 0 {
+            format!(
                 "{} ({} + {}ea)",
-                self.total_time(),
-                self.cycles_base,
+                range_string(self.total_time()),
+                range_string(self.cycles_base),
                 self.cycles_ea
-            ))
+            )
         } else {
-            String::from(format!("{}", self.cycles_base))
+            range_string(self.cycles_base)
+        };
+
+        if self.cycles_taken > 0 {
+            string = format!("{}/{}", self.cycles_base + self.cycles_taken, string);
         }
+
+        if self.cycles_per_count > 0 {
+            string.push_str(&format!(" + {}n", self.cycles_per_count));
+        }
+
+        string
+    }
+
+    /// Same as `get_string`, but with the run time dependent cycles resolved to `extra_cycles`.
+    pub fn get_string_with_extra(&self, extra_cycles: usize) -> String {
+        if self.cycles_per_count == 0 && self.cycles_range == 0 && self.cycles_taken == 0 {
+            return self.get_string();
+        }
+
+        let mut string = format!("{} ({}", self.total_time() + extra_cycles, self.cycles_base);
+        if self.cycles_ea > 0 {
+            string.push_str(&format!(" + {}ea", self.cycles_ea));
+        }
+        string.push_str(&format!(" + {})", extra_cycles));
+
+        string
     }
 }
@@ -1,2 +1,3 @@
 pub mod instruction;
+#[allow(clippy::module_inception)]
 pub mod program;
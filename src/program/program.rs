@@ -1,29 +1,19 @@
 use std::collections::HashMap;
 
-use super::instruction::{self, Instruction};
+use super::instruction::Instruction;
 
 /// A decoded program
 pub struct Program {
-    bytes_len: usize,
-    _bytes: Vec<u8>,
-
     /// Decoded instructions.
     /// key: start_byte, value: instruction
     pub instructions: HashMap<usize, Instruction>,
 }
 
 impl Program {
-    pub fn new(bytes: Vec<u8>) -> Self {
-        let bytes_len = bytes.len();
-        let bytes = bytes.clone();
-
+    pub fn new() -> Self {
         let instructions = HashMap::<usize, Instruction>::with_capacity(2048);
 
-        Self {
-            bytes_len,
-            _bytes: bytes,
-            instructions,
-        }
+        Self { instructions }
     }
 
     pub fn insert_instruction(&mut self, instruction: Instruction) {
@@ -31,13 +21,13 @@ impl Program {
             .insert(instruction.start_byte, instruction);
     }
 
-    pub fn get_instruction_at_byte(&mut self, byte: usize) -> Option<&Instruction> {
-        if byte >= self.bytes_len {
-            return Some(&instruction::END_OF_PROGRAM);
-        } else if self.instructions.contains_key(&byte) {
-            return self.instructions.get(&byte);
-        } else {
-            return Some(&instruction::INVALID_ADDRESS);
-        }
+    /// Returns the instructions sorted by their start byte.
+    pub fn instructions_in_order(&self) -> impl Iterator<Item = &Instruction> {
+        let mut start_bytes: Vec<&usize> = self.instructions.keys().collect();
+        start_bytes.sort_unstable();
+
+        start_bytes
+            .into_iter()
+            .map(|start_byte| &self.instructions[start_byte])
     }
 }
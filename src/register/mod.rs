@@ -1,4 +1,5 @@
 pub mod byte;
+pub mod segment;
 pub mod word;
 
 pub mod util;
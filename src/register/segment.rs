@@ -0,0 +1,14 @@
+pub const ES: u8 = 0b00;
+pub const CS: u8 = 0b01;
+pub const SS: u8 = 0b10;
+pub const DS: u8 = 0b11;
+
+pub fn get_str(register: u8) -> String {
+    match register {
+        ES => "ES".to_string(),
+        CS => "CS".to_string(),
+        SS => "SS".to_string(),
+        DS => "DS".to_string(),
+        _ => "INVALID_REGISTER".to_string(),
+    }
+}
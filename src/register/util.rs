@@ -3,16 +3,11 @@ use crate::{
     register,
 };
 
-pub fn get_register_string_and_operand(
-    reg_bytes: u8,
-    is_word: bool,
-) -> Option<(String, InstructionOperand)> {
+pub fn get_register_operand(reg_bytes: u8, is_word: bool) -> InstructionOperand {
     let mut operand = InstructionOperand::new(OperandType::REGISTER);
     operand.register = Some(reg_bytes);
     operand.register_word = Some(is_word);
-
-    let reg_string = get_register_string(reg_bytes, is_word);
-    Some((reg_string, operand))
+    operand
 }
 
 pub fn get_register_string(reg_bytes: u8, is_word: bool) -> String {
@@ -22,3 +17,10 @@ pub fn get_register_string(reg_bytes: u8, is_word: bool) -> String {
         register::byte::get_str(reg_bytes)
     }
 }
+
+pub fn get_segment_register_operand(reg_bytes: u8) -> InstructionOperand {
+    let mut operand = InstructionOperand::new(OperandType::SEGMENT);
+    operand.register = Some(reg_bytes);
+    operand.register_word = Some(true);
+    operand
+}
@@ -0,0 +1,55 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::program::instruction::Instruction;
+
+const CODE_PAGE_SIZE: usize = 256;
+
+/// Decoded instructions by physical address, so code that runs more than once is only decoded
+/// once. Writing into a page holding cached instructions drops them, which keeps
+/// self-modifying code correct.
+pub struct DecodeCache {
+    /// key: physical address of the first byte, value: instruction
+    instructions: HashMap<usize, Instruction>,
+    /// Pages holding bytes of cached instructions.
+    code_pages: HashSet<usize>,
+}
+
+impl DecodeCache {
+    pub fn new() -> Self {
+        Self {
+            instructions: HashMap::new(),
+            code_pages: HashSet::new(),
+        }
+    }
+
+    pub fn get(&self, address: usize) -> Option<&Instruction> {
+        self.instructions.get(&address)
+    }
+
+    pub fn insert(&mut self, address: usize, instruction: Instruction) {
+        let (first_page, last_page) = Self::get_pages(address, instruction.length);
+        self.code_pages.extend(first_page..=last_page);
+        self.instructions.insert(address, instruction);
+    }
+
+    /// Drops the cached instructions with bytes in the page written at `address`.
+    pub fn invalidate(&mut self, address: usize) {
+        let page = address / CODE_PAGE_SIZE;
+        if !self.code_pages.remove(&page) {
+            return;
+        }
+
+        self.instructions.retain(|start, instruction| {
+            let (first_page, last_page) = Self::get_pages(*start, instruction.length);
+            page < first_page || page > last_page
+        });
+    }
+
+    /// Returns the first and last pages of the bytes in `address..address + length`.
+    fn get_pages(address: usize, length: usize) -> (usize, usize) {
+        (
+            address / CODE_PAGE_SIZE,
+            (address + length.max(1) - 1) / CODE_PAGE_SIZE,
+        )
+    }
+}
@@ -0,0 +1,58 @@
+use crate::{register, simulator::simulator_state::SimulatorState};
+
+const DOS_TERMINATE: u8 = 0x20;
+const DOS_FUNCTION: u8 = 0x21;
+
+/// Installs host handlers for the few DOS services programs use to print and exit:
+/// `INT 20h` and `INT 21h` functions `02h` (write DL), `09h` (write `$` terminated string at
+/// `DS:DX`) and `4Ch` (exit).
+pub fn install_handlers(state: &mut SimulatorState) {
+    state.set_interrupt_handler(DOS_TERMINATE, Box::new(terminate));
+    state.set_interrupt_handler(DOS_FUNCTION, Box::new(dos_function));
+}
+
+fn terminate(state: &mut SimulatorState) {
+    println!("  DOS: program terminated");
+    state.halted = true;
+}
+
+fn dos_function(state: &mut SimulatorState) {
+    let ah = state.registers.read(register::byte::AH, false) as u8;
+
+    match ah {
+        0x02 => {
+            let dl = state.registers.read(register::byte::DL, false) as u8;
+            println!("  DOS output: {:?}", dl as char);
+        }
+        0x09 => {
+            let dx = state.registers.read(register::word::DX, true);
+            let ds = state.registers.read_segment(register::segment::DS);
+            let mut output = String::new();
+            let mut offset = dx;
+            loop {
+                let byte = state.read_mem_byte(SimulatorState::physical_address(ds, offset));
+                if byte == b'$' {
+                    break;
+                }
+                output.push(byte as char);
+                offset = offset.wrapping_add(1);
+
+                // The whole segment was read without finding the terminator.
+                if offset == dx {
+                    println!(
+                        "Error: DOS string at {:04x}:{:04x} has no '$' terminator",
+                        ds, dx
+                    );
+                    state.halted = true;
+                    return;
+                }
+            }
+            println!("  DOS output: {:?}", output);
+        }
+        0x4c => terminate(state),
+        _ => {
+            println!("Error: DOS function 0x{:02x} not supported", ah);
+            state.halted = true;
+        }
+    }
+}
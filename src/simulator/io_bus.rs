@@ -0,0 +1,129 @@
+/// Devices on the I/O port address space, reached by the simulated program through IN and OUT.
+/// Host code implements it to attach (fake) devices.
+pub trait IoBus {
+    fn read_byte(&mut self, port: u16) -> u8;
+
+    fn write_byte(&mut self, port: u16, data: u8);
+
+    /// Reads a word as two byte reads, the low byte from `port` and the high byte from `port + 1`.
+    fn read_word(&mut self, port: u16) -> u16 {
+        let lo = self.read_byte(port);
+        let hi = self.read_byte(port.wrapping_add(1));
+        u16::from_le_bytes([lo, hi])
+    }
+
+    /// Writes a word as two byte writes, the low byte to `port` and the high byte to `port + 1`.
+    fn write_word(&mut self, port: u16, data: u16) {
+        let bytes = data.to_le_bytes();
+        self.write_byte(port, bytes[0]);
+        self.write_byte(port.wrapping_add(1), bytes[1]);
+    }
+}
+
+/// Bus with nothing attached: logs every access and reads `0xFF`, like an unconnected port.
+pub struct LoggingIoBus;
+
+impl IoBus for LoggingIoBus {
+    fn read_byte(&mut self, port: u16) -> u8 {
+        println!("  Port 0x{:04x} -> 0xff", port);
+        0xff
+    }
+
+    fn write_byte(&mut self, port: u16, data: u8) {
+        println!("  Port 0x{:04x} <- 0x{:02x}", port, data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::IoBus;
+    use crate::{program::instruction::TimingPolicy, simulator::simulate::simulate};
+
+    /// Port accesses seen by `FakeIoBus`: port, data and whether it was a word.
+    type Accesses = Rc<RefCell<Vec<(u16, u16, bool)>>>;
+
+    /// Device answering each read with a value derived from the port, recording the accesses.
+    struct FakeIoBus {
+        reads: Accesses,
+        writes: Accesses,
+    }
+
+    impl IoBus for FakeIoBus {
+        fn read_byte(&mut self, port: u16) -> u8 {
+            let data = (port as u8).wrapping_add(1);
+            self.reads.borrow_mut().push((port, data as u16, false));
+            data
+        }
+
+        fn write_byte(&mut self, port: u16, data: u8) {
+            self.writes.borrow_mut().push((port, data as u16, false));
+        }
+
+        fn read_word(&mut self, port: u16) -> u16 {
+            let data = port.wrapping_add(0x0101);
+            self.reads.borrow_mut().push((port, data, true));
+            data
+        }
+
+        fn write_word(&mut self, port: u16, data: u16) {
+            self.writes.borrow_mut().push((port, data, true));
+        }
+    }
+
+    #[test]
+    fn in_and_out_reach_the_device() {
+        // Each OUT writes back what the IN before it read.
+        let bytes = [
+            0xe4, 0x40, //       in al, 0x40
+            0xe6, 0x41, //       out 0x41, al
+            0xe5, 0x42, //       in ax, 0x42
+            0xe7, 0x43, //       out 0x43, ax
+            0xba, 0x34, 0x12, // mov dx, 0x1234
+            0xec, //             in al, dx
+            0xee, //             out dx, al
+            0xed, //             in ax, dx
+            0xef, //             out dx, ax
+            0xf4, //             hlt
+        ];
+        let file = std::env::temp_dir().join("perfaware_8086_io_bus.bin");
+        std::fs::write(&file, bytes).unwrap();
+
+        let reads = Accesses::default();
+        let writes = Accesses::default();
+        let io_bus = FakeIoBus {
+            reads: reads.clone(),
+            writes: writes.clone(),
+        };
+        simulate(
+            file.to_str().unwrap(),
+            false,
+            false,
+            TimingPolicy::Operand,
+            false,
+            false,
+            Box::new(io_bus),
+        );
+        std::fs::remove_file(&file).unwrap();
+
+        assert_eq!(
+            *reads.borrow(),
+            [
+                (0x40, 0x41, false),
+                (0x42, 0x0143, true),
+                (0x1234, 0x35, false),
+                (0x1234, 0x1335, true),
+            ]
+        );
+        assert_eq!(
+            *writes.borrow(),
+            [
+                (0x41, 0x41, false),
+                (0x43, 0x0143, true),
+                (0x1234, 0x35, false),
+                (0x1234, 0x1335, true),
+            ]
+        );
+    }
+}
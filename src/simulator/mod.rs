@@ -1,2 +1,5 @@
+mod decode_cache;
+mod dos;
+pub mod io_bus;
 pub mod simulate;
 mod simulator_state;
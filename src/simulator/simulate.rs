@@ -1,29 +1,57 @@
+use std::fs;
+
 use crate::{
-    decoder::decode,
+    decoder::formatter::FormatOptions,
     op_code::op::OpCode,
-    program::instruction::{Instruction, OperandType},
-    simulator::simulator_state::SimulatorState,
+    program::instruction::{Instruction, InstructionOperand, OperandType, RepPrefix, TimingPolicy},
+    register,
+    simulator::{
+        dos,
+        io_bus::IoBus,
+        simulator_state::{SimulatorState, PROGRAM_SEGMENT},
+    },
 };
 
-pub fn simulate(file_name: &str, dump_memory: bool, estimate_cycles: bool) {
+pub fn simulate(
+    file_name: &str,
+    dump_memory: bool,
+    estimate_cycles: bool,
+    timing_policy: TimingPolicy,
+    stub_dos: bool,
+    use_decode_cache: bool,
+    io_bus: Box<dyn IoBus>,
+) {
     println!("Simulator started with {}", file_name);
 
-    let mut program = match decode(file_name, false, estimate_cycles) {
-        Ok(program) => program,
-        Err(_) => {
-            println!("Error: decoder failed, can't simulate program");
+    let bytes = match fs::read(file_name) {
+        Ok(bytes) => bytes,
+        Err(error) => {
+            println!("Error: can't read program: {}", error);
             return;
         }
     };
 
-    let mut state = SimulatorState::new();
+    let mut state = SimulatorState::new(io_bus);
+    if stub_dos {
+        dos::install_handlers(&mut state);
+    }
+    if use_decode_cache {
+        state.enable_decode_cache();
+    }
+    state.load_program(&bytes, PROGRAM_SEGMENT);
+    println!(
+        "Loaded {} bytes at {:04x}:0000",
+        bytes.len(),
+        PROGRAM_SEGMENT
+    );
     println!("Starting simulation...");
     println!();
 
     loop {
-        let instruction = program.get_instruction_at_byte(state.read_ip() as usize);
-        match instruction {
-            Some(instruction) => {
+        // Instructions are decoded from memory at CS:IP every step, so code written at run time
+        // executes as it is in memory.
+        match state.fetch_instruction().as_ref() {
+            Ok(instruction) => {
                 if estimate_cycles {
                     state.cycles += match &instruction.time_estimation {
                         Some(time_estimation) => time_estimation.total_time(),
@@ -31,57 +59,154 @@ pub fn simulate(file_name: &str, dump_memory: bool, estimate_cycles: bool) {
                         _ => panic!("Error: invalid instruction for time estimation"),
                     }
                 }
+                let flags_before = state.flags_register;
                 match instruction.op_code {
                     OpCode::Mov => {
-                        simulate_mov(&instruction, &mut state, estimate_cycles);
+                        simulate_mov(instruction, &mut state, estimate_cycles);
                     }
-                    OpCode::Add | OpCode::Sub | OpCode::Cmp => {
-                        simulate_add_sub_cmp(&instruction, &mut state, estimate_cycles);
+                    OpCode::Add | OpCode::Adc | OpCode::Sub | OpCode::Sbb | OpCode::Cmp => {
+                        simulate_arithmetic(instruction, &mut state, estimate_cycles);
                     }
-                    OpCode::Jnz => {
-                        simulate_conditional_jmp(&instruction, &mut state, estimate_cycles);
+                    OpCode::And | OpCode::Or | OpCode::Xor | OpCode::Test => {
+                        simulate_logical(instruction, &mut state, estimate_cycles);
                     }
-                    OpCode::Je => {
-                        simulate_conditional_jmp(&instruction, &mut state, estimate_cycles);
+                    OpCode::Inc | OpCode::Dec | OpCode::Neg => {
+                        simulate_inc_dec_neg(instruction, &mut state, estimate_cycles);
                     }
-                    OpCode::Jl => todo!(),
-                    OpCode::Jle => todo!(),
-                    OpCode::Jb => todo!(),
-                    OpCode::Jbe => todo!(),
-                    OpCode::Jp => todo!(),
-                    OpCode::Jo => todo!(),
-                    OpCode::Js => todo!(),
-                    OpCode::Jnl => todo!(),
-                    OpCode::Jg => todo!(),
-                    OpCode::Jnb => todo!(),
-                    OpCode::Ja => todo!(),
-                    OpCode::Jnp => todo!(),
-                    OpCode::Jno => todo!(),
-                    OpCode::Jns => todo!(),
-                    OpCode::Loop => todo!(),
-                    OpCode::Loopz => todo!(),
-                    OpCode::Loopnz => todo!(),
-                    OpCode::Jcxz => todo!(),
-
-                    OpCode::Invalid => {
-                        println!("Error: can't simulate instruction: invalid op code");
-                        break;
+                    OpCode::Not => {
+                        simulate_not(instruction, &mut state, estimate_cycles);
                     }
-                    OpCode::InvalidAddress => {
-                        println!("Error: can't simulate instruction: invalid address");
-                        break;
+                    OpCode::Mul | OpCode::Imul => {
+                        simulate_mul(instruction, &mut state, estimate_cycles, timing_policy);
+                    }
+                    OpCode::Div | OpCode::Idiv => {
+                        simulate_div(instruction, &mut state, estimate_cycles, timing_policy);
+                    }
+                    OpCode::Shl
+                    | OpCode::Shr
+                    | OpCode::Sar
+                    | OpCode::Rol
+                    | OpCode::Ror
+                    | OpCode::Rcl
+                    | OpCode::Rcr => {
+                        simulate_shift_rotate(instruction, &mut state, estimate_cycles);
+                    }
+                    OpCode::Call | OpCode::CallFar | OpCode::Jmp | OpCode::JmpFar => {
+                        simulate_jmp_call(instruction, &mut state, estimate_cycles);
+                    }
+                    OpCode::Ret | OpCode::Retf => {
+                        simulate_ret(instruction, &mut state, estimate_cycles);
+                    }
+                    OpCode::Int | OpCode::Int3 | OpCode::Into => {
+                        simulate_int(instruction, &mut state, estimate_cycles);
+                    }
+                    OpCode::Iret => {
+                        print_instruction_info(instruction, &state, estimate_cycles);
+                        state.interrupt_return();
+                    }
+                    OpCode::Jnz
+                    | OpCode::Je
+                    | OpCode::Jl
+                    | OpCode::Jle
+                    | OpCode::Jb
+                    | OpCode::Jbe
+                    | OpCode::Jp
+                    | OpCode::Jo
+                    | OpCode::Js
+                    | OpCode::Jnl
+                    | OpCode::Jg
+                    | OpCode::Jnb
+                    | OpCode::Ja
+                    | OpCode::Jnp
+                    | OpCode::Jno
+                    | OpCode::Jns
+                    | OpCode::Loop
+                    | OpCode::Loopz
+                    | OpCode::Loopnz
+                    | OpCode::Jcxz => {
+                        simulate_conditional_jmp(instruction, &mut state, estimate_cycles);
+                    }
+                    OpCode::Movs | OpCode::Cmps | OpCode::Scas | OpCode::Lods | OpCode::Stos => {
+                        simulate_string(instruction, &mut state, estimate_cycles);
+                    }
+                    OpCode::Push => {
+                        simulate_push(instruction, &mut state, estimate_cycles);
+                    }
+                    OpCode::In | OpCode::Out => {
+                        simulate_in_out(instruction, &mut state, estimate_cycles);
+                    }
+                    OpCode::Daa | OpCode::Das | OpCode::Aaa | OpCode::Aas => {
+                        simulate_decimal_adjust(instruction, &mut state, estimate_cycles);
+                    }
+                    OpCode::Aam | OpCode::Aad => {
+                        simulate_aam_aad(instruction, &mut state, estimate_cycles);
+                    }
+                    OpCode::Cbw | OpCode::Cwd => {
+                        simulate_sign_extend(instruction, &mut state, estimate_cycles);
+                    }
+                    OpCode::Clc
+                    | OpCode::Stc
+                    | OpCode::Cmc
+                    | OpCode::Cld
+                    | OpCode::Std
+                    | OpCode::Cli
+                    | OpCode::Sti => {
+                        simulate_flag_control(instruction, &mut state, estimate_cycles);
+                    }
+                    OpCode::Hlt => {
+                        // There are no hardware interrupts to resume from, so HLT ends the program.
+                        print_instruction_info(instruction, &state, estimate_cycles);
+                        state.advance_ip(instruction);
+                        state.halted = true;
+                    }
+                    // Without a coprocessor, WAIT doesn't wait and ESC has nothing to hand over.
+                    OpCode::Wait | OpCode::Esc => {
+                        print_instruction_info(instruction, &state, estimate_cycles);
+                        state.advance_ip(instruction);
                     }
+                    OpCode::Xchg => {
+                        simulate_xchg(instruction, &mut state, estimate_cycles);
+                    }
+                    OpCode::Nop => {
+                        print_instruction_info(instruction, &state, estimate_cycles);
+                        state.advance_ip(instruction);
+                    }
+                    OpCode::Xlat => {
+                        simulate_xlat(instruction, &mut state, estimate_cycles);
+                    }
+                    OpCode::Lea | OpCode::Lds | OpCode::Les => {
+                        simulate_load_address(instruction, &mut state, estimate_cycles);
+                    }
+                    OpCode::Lahf | OpCode::Sahf | OpCode::Pushf | OpCode::Popf => {
+                        simulate_flags_transfer(instruction, &mut state, estimate_cycles);
+                    }
+                    OpCode::Pop => {
+                        simulate_pop(instruction, &mut state, estimate_cycles);
+                    }
+
                     OpCode::EndOfProgram => {
                         println!("\nReached end of program");
                         break;
                     }
                 }
+                if state.flags_register != flags_before {
+                    state.flags_register.print_change(&flags_before);
+                }
             }
-            None => {
-                println!("Error: no instruction decoded to simulate");
+            Err(_) => {
+                println!(
+                    "Error: can't decode instruction at {:04x}:{:04x}",
+                    state.registers.read_segment(register::segment::CS),
+                    state.read_ip()
+                );
                 break;
             }
         };
+
+        if state.halted {
+            println!("\nSimulation halted");
+            break;
+        }
     }
 
     println!("\nFinal state");
@@ -98,11 +223,22 @@ pub fn simulate(file_name: &str, dump_memory: bool, estimate_cycles: bool) {
 }
 
 fn print_instruction_info(instruction: &Instruction, state: &SimulatorState, print_cycles: bool) {
+    print_instruction_info_with_extra(instruction, state, print_cycles, 0);
+}
+
+/// Prints the instruction, with its run time dependent cycles (see `InstructionTime`)
+/// resolved to `extra_cycles`.
+fn print_instruction_info_with_extra(
+    instruction: &Instruction,
+    state: &SimulatorState,
+    print_cycles: bool,
+    extra_cycles: usize,
+) {
     let cycles_string = if print_cycles {
         let time_estimation = instruction.time_estimation.unwrap();
         format!(
             " ; Cycles: +{} = {}",
-            time_estimation.get_string(),
+            time_estimation.get_string_with_extra(extra_cycles),
             state.cycles
         )
     } else {
@@ -111,211 +247,527 @@ fn print_instruction_info(instruction: &Instruction, state: &SimulatorState, pri
 
     println!(
         "{}{}",
-        instruction.decoded_string.as_ref().unwrap(),
+        FormatOptions::default()
+            .formatter()
+            .format(instruction, None),
         cycles_string
     );
 }
 
 fn simulate_mov(instruction: &Instruction, state: &mut SimulatorState, print_cycles: bool) {
-    print_instruction_info(&instruction, &state, print_cycles);
-    state.write_ip(state.read_ip() + instruction.length as u16);
+    print_instruction_info(instruction, state, print_cycles);
+    state.advance_ip(instruction);
 
-    let src_operand = &instruction.src_operand.as_ref().unwrap();
+    let src_operand = instruction.src_operand.as_ref().unwrap();
+    let data = read_operand(src_operand, state);
 
-    let data = match src_operand.operand_type {
-        OperandType::REGISTER => state.registers.read(
-            src_operand.register.unwrap(),
-            src_operand.register_word.unwrap(),
-        ),
-        OperandType::EAC => {
-            let mut address: u16 = 0;
-            address += match src_operand.eac_reg_0 {
-                Some(reg) => state.registers.read(reg, true),
-                None => 0,
-            };
-            address += match src_operand.eac_reg_1 {
-                Some(reg) => state.registers.read(reg, true),
-                None => 0,
-            };
-            address += match src_operand.eac_displacement {
-                Some(displacement) => displacement,
-                None => 0,
-            };
+    let dest_operand = instruction.dest_operand.as_ref().unwrap();
+    write_operand(dest_operand, state, data);
+}
 
-            let mut d = state.read_mem_byte(address as usize) as u16;
-            if src_operand.register_word.unwrap() {
-                d += state.read_mem_byte(address as usize + 1) as u16 * 256;
-            }
+fn simulate_arithmetic(instruction: &Instruction, state: &mut SimulatorState, print_cycles: bool) {
+    print_instruction_info(instruction, state, print_cycles);
+    state.advance_ip(instruction);
 
-            d
+    let src_operand = instruction.src_operand.as_ref().unwrap();
+    let data_src = read_operand(src_operand, state);
+
+    let dest_operand = instruction.dest_operand.as_ref().unwrap();
+    let data_dest = read_operand(dest_operand, state);
+    let word = dest_operand.register_word.unwrap();
+
+    if matches!(
+        dest_operand.operand_type,
+        OperandType::SEGMENT | OperandType::LITERAL
+    ) {
+        panic!("Error: ADD/ADC/SUB/SBB/CMP to segment register or literal is not a valid operation")
+    }
+
+    let carry = state.flags_register.carry;
+    let result = match instruction.op_code {
+        OpCode::Add => add_with_flags(state, data_dest, data_src, false, word),
+        OpCode::Adc => add_with_flags(state, data_dest, data_src, carry, word),
+        OpCode::Sub | OpCode::Cmp => sub_with_flags(state, data_dest, data_src, false, word),
+        OpCode::Sbb => sub_with_flags(state, data_dest, data_src, carry, word),
+        _ => panic!("Error: invalid opcode for ADD/ADC/SUB/SBB/CMP instruction"),
+    };
+
+    if instruction.op_code != OpCode::Cmp {
+        write_operand(dest_operand, state, result);
+    }
+}
+
+/// Adds `src` and the carry in to `dest` with the given width, setting CF, AF, OF, SF, ZF and PF.
+fn add_with_flags(
+    state: &mut SimulatorState,
+    dest: u16,
+    src: u16,
+    carry_in: bool,
+    word: bool,
+) -> u16 {
+    let msb: u16 = if word { 0x8000 } else { 0x80 };
+    let mask: u16 = if word { 0xffff } else { 0xff };
+    let (dest, src) = (dest & mask, src & mask);
+
+    let wide = dest as u32 + src as u32 + carry_in as u32;
+    let result = wide as u16 & mask;
+
+    state.flags_register.carry = wide > mask as u32;
+    state.flags_register.auxiliary_carry = (dest ^ src ^ result) & 0x10 != 0;
+    state.flags_register.overflow = (dest ^ result) & (src ^ result) & msb != 0;
+    state.flags_register.set_sign_zero_parity(result, word);
+
+    result
+}
+
+/// Subtracts `src` and the borrow in from `dest` with the given width, setting CF, AF, OF, SF, ZF
+/// and PF.
+fn sub_with_flags(
+    state: &mut SimulatorState,
+    dest: u16,
+    src: u16,
+    borrow_in: bool,
+    word: bool,
+) -> u16 {
+    let msb: u16 = if word { 0x8000 } else { 0x80 };
+    let mask: u16 = if word { 0xffff } else { 0xff };
+    let (dest, src) = (dest & mask, src & mask);
+
+    let result = dest.wrapping_sub(src).wrapping_sub(borrow_in as u16) & mask;
+
+    state.flags_register.carry = (dest as u32) < src as u32 + borrow_in as u32;
+    state.flags_register.auxiliary_carry = (dest ^ src ^ result) & 0x10 != 0;
+    state.flags_register.overflow = (dest ^ src) & (dest ^ result) & msb != 0;
+    state.flags_register.set_sign_zero_parity(result, word);
+
+    result
+}
+
+fn simulate_logical(instruction: &Instruction, state: &mut SimulatorState, print_cycles: bool) {
+    print_instruction_info(instruction, state, print_cycles);
+    state.advance_ip(instruction);
+
+    let src_operand = instruction.src_operand.as_ref().unwrap();
+    let data_src = read_operand(src_operand, state);
+
+    let dest_operand = instruction.dest_operand.as_ref().unwrap();
+    let data_dest = read_operand(dest_operand, state);
+
+    let result = match instruction.op_code {
+        OpCode::And | OpCode::Test => data_dest & data_src,
+        OpCode::Or => data_dest | data_src,
+        OpCode::Xor => data_dest ^ data_src,
+        _ => panic!("Error: invalid opcode for AND/OR/XOR/TEST instruction"),
+    };
+
+    // Logical instructions always clear CF and OF. AF is undefined, the 8086 clears it.
+    state.flags_register.carry = false;
+    state.flags_register.auxiliary_carry = false;
+    state.flags_register.overflow = false;
+    state
+        .flags_register
+        .set_sign_zero_parity(result, dest_operand.register_word.unwrap());
+
+    if instruction.op_code != OpCode::Test {
+        write_operand(dest_operand, state, result);
+    }
+}
+
+fn simulate_inc_dec_neg(instruction: &Instruction, state: &mut SimulatorState, print_cycles: bool) {
+    print_instruction_info(instruction, state, print_cycles);
+    state.advance_ip(instruction);
+
+    let dest_operand = instruction.dest_operand.as_ref().unwrap();
+    let word = dest_operand.register_word.unwrap();
+    let msb: u16 = if word { 0x8000 } else { 0x80 };
+    let mask: u16 = if word { 0xffff } else { 0xff };
+
+    let data = read_operand(dest_operand, state) & mask;
+
+    // INC and DEC leave CF untouched, which lets them drive multi-word arithmetic loops.
+    let result = match instruction.op_code {
+        OpCode::Inc => {
+            let r = data.wrapping_add(1) & mask;
+            state.flags_register.auxiliary_carry = r & 0xf == 0;
+            state.flags_register.overflow = r == msb;
+            r
         }
-        OperandType::LITERAL => src_operand.literal.unwrap(),
+        OpCode::Dec => {
+            let r = data.wrapping_sub(1) & mask;
+            state.flags_register.auxiliary_carry = r & 0xf == 0xf;
+            state.flags_register.overflow = data == msb;
+            r
+        }
+        OpCode::Neg => {
+            let r = 0u16.wrapping_sub(data) & mask;
+            state.flags_register.carry = data != 0;
+            state.flags_register.auxiliary_carry = data & 0xf != 0;
+            state.flags_register.overflow = data == msb;
+            r
+        }
+        _ => panic!("Error: invalid opcode for INC/DEC/NEG instruction"),
     };
+    state.flags_register.set_sign_zero_parity(result, word);
+
+    write_operand(dest_operand, state, result);
+}
+
+fn simulate_not(instruction: &Instruction, state: &mut SimulatorState, print_cycles: bool) {
+    print_instruction_info(instruction, state, print_cycles);
+    state.advance_ip(instruction);
 
+    // NOT doesn't affect any flags.
     let dest_operand = instruction.dest_operand.as_ref().unwrap();
-    match dest_operand.operand_type {
-        OperandType::REGISTER => state.registers.write(
-            data,
-            dest_operand.register.unwrap(),
-            dest_operand.register_word.unwrap(),
-        ),
-        OperandType::EAC => {
-            let mut address: u16 = 0;
-            address += match dest_operand.eac_reg_0 {
-                Some(reg) => state.registers.read(reg, true),
-                None => 0,
-            };
-            address += match dest_operand.eac_reg_1 {
-                Some(reg) => state.registers.read(reg, true),
-                None => 0,
-            };
-            address += match dest_operand.eac_displacement {
-                Some(displacement) => displacement,
-                None => 0,
+    let data = read_operand(dest_operand, state);
+    write_operand(dest_operand, state, !data);
+}
+
+/// Simulates DAA/DAS (packed BCD) and AAA/AAS (unpacked BCD) after an addition or subtraction.
+/// The 8086 applies the correction with its adder, so the flags the manual leaves undefined are
+/// the ones of that addition or subtraction.
+fn simulate_decimal_adjust(
+    instruction: &Instruction,
+    state: &mut SimulatorState,
+    print_cycles: bool,
+) {
+    use register::byte::{AH, AL};
+
+    print_instruction_info(instruction, state, print_cycles);
+    state.advance_ip(instruction);
+
+    let al = state.registers.read(AL, false);
+    let carry = state.flags_register.carry;
+    let adjust_low = al & 0x0f > 9 || state.flags_register.auxiliary_carry;
+
+    match instruction.op_code {
+        OpCode::Daa | OpCode::Das => {
+            let adjust_high = al > 0x99 || carry;
+            let correction = if adjust_low { 0x06 } else { 0 } + if adjust_high { 0x60 } else { 0 };
+
+            let result = if instruction.op_code == OpCode::Daa {
+                add_with_flags(state, al, correction, false, false)
+            } else {
+                sub_with_flags(state, al, correction, false, false)
             };
+            state.flags_register.auxiliary_carry = adjust_low;
+            state.flags_register.carry = adjust_high;
+
+            state.registers.write(result, AL, false);
+        }
+        OpCode::Aaa | OpCode::Aas => {
+            let correction = if adjust_low { 0x06 } else { 0 };
+            let ah = state.registers.read(AH, false);
 
-            if src_operand.register_word.unwrap() {
-                let bytes = data.to_le_bytes();
-                state.write_mem_byte(address as usize, bytes[0]);
-                state.write_mem_byte(address as usize + 1, bytes[1]);
+            // Unlike later CPUs, the 8086 adjusts AL alone and then AH, so AL never carries into AH.
+            let (result, ah) = if instruction.op_code == OpCode::Aaa {
+                let result = add_with_flags(state, al, correction, false, false);
+                (result, ah.wrapping_add(adjust_low as u16))
             } else {
-                state.write_mem_byte(address as usize, data as u8)
+                let result = sub_with_flags(state, al, correction, false, false);
+                (result, ah.wrapping_sub(adjust_low as u16))
+            };
+            state.flags_register.auxiliary_carry = adjust_low;
+            state.flags_register.carry = adjust_low;
+
+            state
+                .registers
+                .write((ah & 0xff) << 8 | (result & 0x0f), register::word::AX, true);
+        }
+        _ => panic!("Error: invalid opcode for DAA/DAS/AAA/AAS instruction"),
+    }
+}
+
+/// Simulates AAM (AL into unpacked BCD digits in AH:AL) and AAD (unpacked BCD digits in AH:AL
+/// into AL), in the base given by the instruction's immediate byte.
+fn simulate_aam_aad(instruction: &Instruction, state: &mut SimulatorState, print_cycles: bool) {
+    use register::{
+        byte::{AH, AL},
+        word::AX,
+    };
+
+    print_instruction_info(instruction, state, print_cycles);
+    state.advance_ip(instruction);
+
+    let base = instruction.dest_operand.unwrap().literal.unwrap();
+    let al = state.registers.read(AL, false);
+    let ah = state.registers.read(AH, false);
+
+    match instruction.op_code {
+        OpCode::Aam => {
+            // AAM divides, so a zero base is a divide error.
+            if base == 0 {
+                state.interrupt(0);
+                return;
             }
+
+            let (quotient, remainder) = (al / base, al % base);
+            state.flags_register.carry = false;
+            state.flags_register.auxiliary_carry = false;
+            state.flags_register.overflow = false;
+            state.flags_register.set_sign_zero_parity(remainder, false);
+
+            state.registers.write(quotient << 8 | remainder, AX, true);
         }
-        OperandType::LITERAL => todo!(),
+        OpCode::Aad => {
+            // The last step is a byte addition, which sets every arithmetic flag.
+            let product = (ah * base) & 0xff;
+            let result = add_with_flags(state, al, product, false, false);
+
+            state.registers.write(result, AX, true);
+        }
+        _ => panic!("Error: invalid opcode for AAM/AAD instruction"),
     }
 }
 
-fn simulate_add_sub_cmp(instruction: &Instruction, state: &mut SimulatorState, print_cycles: bool) {
-    print_instruction_info(&instruction, &state, print_cycles);
-    state.write_ip(state.read_ip() + instruction.length as u16);
+/// Simulates CBW (AL into AX) and CWD (AX into DX:AX) sign extension.
+fn simulate_sign_extend(instruction: &Instruction, state: &mut SimulatorState, print_cycles: bool) {
+    use register::word::{AX, DX};
 
-    let src_operand = &instruction.src_operand.as_ref().unwrap();
-    let data_src = match src_operand.operand_type {
-        OperandType::REGISTER => state.registers.read(
-            src_operand.register.unwrap(),
-            src_operand.register_word.unwrap(),
-        ),
-        OperandType::LITERAL => src_operand.literal.unwrap(),
-        OperandType::EAC => {
-            let mut address: u16 = 0;
-            address += match src_operand.eac_reg_0 {
-                Some(reg) => state.registers.read(reg, true),
-                None => 0,
-            };
-            address += match src_operand.eac_reg_1 {
-                Some(reg) => state.registers.read(reg, true),
-                None => 0,
-            };
-            address += match src_operand.eac_displacement {
-                Some(displacement) => displacement,
-                None => 0,
-            };
+    print_instruction_info(instruction, state, print_cycles);
+    state.advance_ip(instruction);
+
+    let ax = state.registers.read(AX, true);
+    match instruction.op_code {
+        OpCode::Cbw => state.registers.write(ax as u8 as i8 as u16, AX, true),
+        OpCode::Cwd => {
+            let dx = if ax & 0x8000 != 0 { 0xffff } else { 0 };
+            state.registers.write(dx, DX, true);
+        }
+        _ => panic!("Error: invalid opcode for CBW/CWD instruction"),
+    }
+}
+
+fn simulate_shift_rotate(
+    instruction: &Instruction,
+    state: &mut SimulatorState,
+    print_cycles: bool,
+) {
+    let src_operand = instruction.src_operand.as_ref().unwrap();
+    let count = read_operand(src_operand, state) as u8;
+
+    let mut extra_cycles = 0;
+    if print_cycles {
+        let time_estimation = instruction.time_estimation.unwrap();
+        extra_cycles = time_estimation.cycles_per_count * count as usize;
+        state.cycles += extra_cycles;
+    }
+
+    print_instruction_info_with_extra(instruction, state, print_cycles, extra_cycles);
+    state.advance_ip(instruction);
+
+    // A zero count leaves both the operand and the flags untouched.
+    if count == 0 {
+        return;
+    }
+
+    let dest_operand = instruction.dest_operand.as_ref().unwrap();
+    let word = dest_operand.register_word.unwrap();
+    let msb: u16 = if word { 0x8000 } else { 0x80 };
+    let mask: u16 = if word { 0xffff } else { 0xff };
 
-            let mut d = state.read_mem_byte(address as usize) as u16;
-            if src_operand.register_word.unwrap() {
-                d += state.read_mem_byte(address as usize + 1) as u16 * 256;
+    let mut data = read_operand(dest_operand, state) & mask;
+    let mut carry = state.flags_register.carry;
+    let mut overflow = state.flags_register.overflow;
+
+    // The 8086 shifts one bit per iteration, and the documented-undefined OF for multi-bit
+    // counts is whatever the last single bit iteration left behind.
+    for _ in 0..count {
+        match instruction.op_code {
+            OpCode::Shl => {
+                carry = data & msb != 0;
+                data = (data << 1) & mask;
+                overflow = (data & msb != 0) != carry;
+            }
+            OpCode::Shr => {
+                carry = data & 1 != 0;
+                overflow = data & msb != 0;
+                data >>= 1;
+            }
+            OpCode::Sar => {
+                carry = data & 1 != 0;
+                overflow = false;
+                data = (data >> 1) | (data & msb);
+            }
+            OpCode::Rol => {
+                carry = data & msb != 0;
+                data = ((data << 1) & mask) | carry as u16;
+                overflow = (data & msb != 0) != carry;
+            }
+            OpCode::Ror => {
+                carry = data & 1 != 0;
+                data = (data >> 1) | if carry { msb } else { 0 };
+                overflow = (data & msb != 0) != (data & (msb >> 1) != 0);
+            }
+            OpCode::Rcl => {
+                let carry_in = carry;
+                carry = data & msb != 0;
+                data = ((data << 1) & mask) | carry_in as u16;
+                overflow = (data & msb != 0) != carry;
             }
+            OpCode::Rcr => {
+                let carry_in = carry;
+                carry = data & 1 != 0;
+                data = (data >> 1) | if carry_in { msb } else { 0 };
+                overflow = (data & msb != 0) != (data & (msb >> 1) != 0);
+            }
+            _ => panic!("Error: invalid opcode for shift/rotate instruction"),
+        }
+    }
+
+    state.flags_register.carry = carry;
+    state.flags_register.overflow = overflow;
+
+    // Rotates only affect CF and OF.
+    if matches!(instruction.op_code, OpCode::Shl | OpCode::Shr | OpCode::Sar) {
+        state.flags_register.set_sign_zero_parity(data, word);
+    }
+
+    write_operand(dest_operand, state, data);
+}
+
+fn simulate_mul(
+    instruction: &Instruction,
+    state: &mut SimulatorState,
+    print_cycles: bool,
+    timing_policy: TimingPolicy,
+) {
+    use register::word::{AX, DX};
+
+    let src_operand = instruction.dest_operand.as_ref().unwrap();
+    let word = src_operand.register_word.unwrap();
+    let data_src = read_operand(src_operand, state);
+
+    // The multiplier's set bits drive the microcode loop, so they pick the time in the range.
+    let mut extra_cycles = 0;
+    if print_cycles {
+        let bits = if word { 16 } else { 8 };
+        let ones = if word {
+            data_src.count_ones()
+        } else {
+            (data_src as u8).count_ones()
+        };
+        let time_estimation = instruction.time_estimation.unwrap();
+        extra_cycles = time_estimation.get_range_cycles(timing_policy, (ones as usize, bits));
+        state.cycles += extra_cycles;
+    }
 
-            d
+    print_instruction_info_with_extra(instruction, state, print_cycles, extra_cycles);
+    state.advance_ip(instruction);
+
+    let ax = state.registers.read(AX, true);
+    let signed = instruction.op_code == OpCode::Imul;
+
+    // CF and OF are set when the upper half of the result is significant.
+    let upper_significant = if word {
+        let result = if signed {
+            (ax as i16 as i32 * data_src as i16 as i32) as u32
+        } else {
+            ax as u32 * data_src as u32
+        };
+        let (lo, hi) = (result as u16, (result >> 16) as u16);
+        state.registers.write(lo, AX, true);
+        state.registers.write(hi, DX, true);
+
+        if signed {
+            hi != if lo & 0x8000 != 0 { 0xffff } else { 0 }
+        } else {
+            hi != 0
+        }
+    } else {
+        let result = if signed {
+            (ax as u8 as i8 as i16 * data_src as u8 as i8 as i16) as u16
+        } else {
+            (ax as u8) as u16 * (data_src as u8) as u16
+        };
+        state.registers.write(result, AX, true);
+
+        if signed {
+            result as i16 != result as u8 as i8 as i16
+        } else {
+            result & 0xff00 != 0
         }
     };
 
-    let dest_operand = instruction.dest_operand.as_ref().unwrap();
-    let data_dest = match dest_operand.operand_type {
-        OperandType::REGISTER => state.registers.read(
-            dest_operand.register.unwrap(),
-            dest_operand.register_word.unwrap(),
-        ),
-        OperandType::EAC => {
-            let mut addr = dest_operand.eac_displacement.unwrap_or_default();
-            addr += match dest_operand.eac_reg_0 {
-                Some(reg) => state.registers.read(reg, true),
-                None => 0,
-            };
-            addr += match dest_operand.eac_reg_1 {
-                Some(reg) => state.registers.read(reg, true),
-                None => 0,
-            };
-            state.read_mem_word(addr as usize)
+    state.flags_register.carry = upper_significant;
+    state.flags_register.overflow = upper_significant;
+}
+
+fn simulate_div(
+    instruction: &Instruction,
+    state: &mut SimulatorState,
+    print_cycles: bool,
+    timing_policy: TimingPolicy,
+) {
+    use register::word::{AX, DX};
+
+    let src_operand = instruction.dest_operand.as_ref().unwrap();
+    let word = src_operand.register_word.unwrap();
+    let divisor = read_operand(src_operand, state);
+    let signed = instruction.op_code == OpCode::Idiv;
+
+    let ax = state.registers.read(AX, true);
+    let dx = state.registers.read(DX, true);
+
+    // (quotient, remainder), or None on a divide error.
+    let result: Option<(u16, u16)> = match (word, signed) {
+        _ if (word && divisor == 0) || (!word && divisor as u8 == 0) => None,
+        (true, false) => {
+            let dividend = (dx as u32) << 16 | ax as u32;
+            let quotient = dividend / divisor as u32;
+            (quotient <= 0xffff).then_some((quotient as u16, (dividend % divisor as u32) as u16))
+        }
+        (true, true) => {
+            let dividend = ((dx as u32) << 16 | ax as u32) as i32;
+            let quotient = dividend.wrapping_div(divisor as i16 as i32);
+            // The 8086 also faults on the most negative quotient.
+            (-0x7fff..=0x7fff).contains(&quotient).then_some((
+                quotient as u16,
+                dividend.wrapping_rem(divisor as i16 as i32) as u16,
+            ))
+        }
+        (false, false) => {
+            let quotient = ax / divisor as u8 as u16;
+            (quotient <= 0xff).then_some((quotient, ax % divisor as u8 as u16))
+        }
+        (false, true) => {
+            let dividend = ax as i16;
+            let quotient = dividend.wrapping_div(divisor as u8 as i8 as i16);
+            (-0x7f..=0x7f).contains(&quotient).then_some((
+                quotient as u16,
+                dividend.wrapping_rem(divisor as u8 as i8 as i16) as u16,
+            ))
         }
-        OperandType::LITERAL => dest_operand.literal.unwrap(),
     };
 
-    match dest_operand.operand_type {
-        OperandType::REGISTER => {
-            let result: Option<u16> = match instruction.op_code {
-                OpCode::Add => {
-                    let r = (data_dest as i16 + data_src as i16) as u16;
-                    state.flags_register.zero = r == 0;
-                    state.flags_register.sign = (r & 0x8000) >> 15 == 1;
-                    Some(r)
-                }
-                OpCode::Sub => {
-                    let r = (data_dest as i16 - data_src as i16) as u16;
-                    state.flags_register.zero = r == 0;
-                    state.flags_register.sign = (r & 0x8000) >> 15 == 1;
-                    Some(r)
-                }
-                OpCode::Cmp => {
-                    let r = (data_dest as i16 - data_src as i16) as u16;
-                    state.flags_register.zero = r == 0;
-                    state.flags_register.sign = (r & 0x8000) >> 15 == 1;
-                    None
-                }
-                _ => panic!("Error: invalid opcode for ADD/SUB/CMP instruction"),
-            };
-            if result.is_some() {
-                let r = result.unwrap();
-                state.registers.write(
-                    r,
-                    dest_operand.register.unwrap(),
-                    dest_operand.register_word.unwrap(),
-                );
-            };
-            state.flags_register.print();
+    // The quotient's set bits drive the microcode loop, so they pick the time in the range.
+    let mut extra_cycles = 0;
+    if print_cycles {
+        let bits = if word { 16 } else { 8 };
+        let ones = match result {
+            Some((quotient, _)) if word => quotient.count_ones(),
+            Some((quotient, _)) => (quotient as u8).count_ones(),
+            None => 0,
+        };
+        let time_estimation = instruction.time_estimation.unwrap();
+        extra_cycles = time_estimation.get_range_cycles(timing_policy, (ones as usize, bits));
+        state.cycles += extra_cycles;
+    }
+
+    print_instruction_info_with_extra(instruction, state, print_cycles, extra_cycles);
+    state.advance_ip(instruction);
+
+    match result {
+        Some((quotient, remainder)) if word => {
+            state.registers.write(quotient, AX, true);
+            state.registers.write(remainder, DX, true);
         }
-        OperandType::EAC => {
-            let result: Option<u16> = match instruction.op_code {
-                OpCode::Add => {
-                    let r = (data_dest as i16 + data_src as i16) as u16;
-                    state.flags_register.zero = r == 0;
-                    state.flags_register.sign = (r & 0x8000) >> 15 == 1;
-                    Some(r)
-                }
-                OpCode::Sub => {
-                    let r = (data_dest as i16 - data_src as i16) as u16;
-                    state.flags_register.zero = r == 0;
-                    state.flags_register.sign = (r & 0x8000) >> 15 == 1;
-                    Some(r)
-                }
-                OpCode::Cmp => {
-                    let r = (data_dest as i16 - data_src as i16) as u16;
-                    state.flags_register.zero = r == 0;
-                    state.flags_register.sign = (r & 0x8000) >> 15 == 1;
-                    None
-                }
-                _ => panic!("Error: invalid opcode for ADD/SUB/CMP instruction"),
-            };
-            if result.is_some() {
-                // Get memory address
-                let mut addr = dest_operand.eac_displacement.unwrap_or_default();
-                addr += match dest_operand.eac_reg_0 {
-                    Some(reg) => state.registers.read(reg, true),
-                    None => 0,
-                };
-                addr += match dest_operand.eac_reg_1 {
-                    Some(reg) => state.registers.read(reg, true),
-                    None => 0,
-                };
-
-                // Write data to memory
-                let r = result.unwrap();
-                state.write_mem_word(addr as usize, r);
-            };
-            state.flags_register.print();
+        Some((quotient, remainder)) => {
+            state
+                .registers
+                .write((remainder & 0xff) << 8 | (quotient & 0xff), AX, true);
         }
-        OperandType::LITERAL => panic!("Error: ADD/SUB/CMP to literal is not a valid operation"),
+        // Division by zero or quotient overflow, the 8086 pushes the next instruction's address.
+        None => state.interrupt(0),
     }
 }
 
@@ -324,26 +776,522 @@ fn simulate_conditional_jmp(
     state: &mut SimulatorState,
     print_cycles: bool,
 ) {
-    print_instruction_info(&instruction, &state, print_cycles);
-    state.write_ip(state.read_ip() + instruction.length as u16);
-
-    match (instruction.op_code, state.flags_register.zero) {
-        (OpCode::Jnz, false) | (OpCode::Je, true) => {
-            let dest_operand = instruction.dest_operand.as_ref().unwrap();
-            let data = match dest_operand.operand_type {
-                OperandType::LITERAL => dest_operand.literal.unwrap(),
-                _ => {
-                    println!("Error: invalid increment data type in conditional jump");
-                    0
-                }
-            };
-            let increment = data as i16;
-            let mut new_ip = state.read_ip() as i16 + increment;
-            if increment < 0 {
-                new_ip -= instruction.length as i16;
+    use register::word::CX;
+
+    let flags = &state.flags_register;
+    let (carry, parity, zero, sign, overflow) = (
+        flags.carry,
+        flags.parity,
+        flags.zero,
+        flags.sign,
+        flags.overflow,
+    );
+
+    // LOOP variants decrement CX before testing it, without changing any flags.
+    let mut cx = state.registers.read(CX, true);
+    if matches!(
+        instruction.op_code,
+        OpCode::Loop | OpCode::Loopz | OpCode::Loopnz
+    ) {
+        cx = cx.wrapping_sub(1);
+    }
+
+    let taken = match instruction.op_code {
+        OpCode::Je => zero,
+        OpCode::Jnz => !zero,
+        OpCode::Jl => sign != overflow,
+        OpCode::Jle => zero || sign != overflow,
+        OpCode::Jnl => sign == overflow,
+        OpCode::Jg => !zero && sign == overflow,
+        OpCode::Jb => carry,
+        OpCode::Jbe => carry || zero,
+        OpCode::Jnb => !carry,
+        OpCode::Ja => !carry && !zero,
+        OpCode::Jp => parity,
+        OpCode::Jnp => !parity,
+        OpCode::Jo => overflow,
+        OpCode::Jno => !overflow,
+        OpCode::Js => sign,
+        OpCode::Jns => !sign,
+        OpCode::Loop => cx != 0,
+        OpCode::Loopz => cx != 0 && zero,
+        OpCode::Loopnz => cx != 0 && !zero,
+        OpCode::Jcxz => cx == 0,
+        _ => panic!("Error: invalid opcode for conditional jump"),
+    };
+
+    let mut extra_cycles = 0;
+    if print_cycles && taken {
+        extra_cycles = instruction.time_estimation.unwrap().cycles_taken;
+        state.cycles += extra_cycles;
+    }
+
+    print_instruction_info_with_extra(instruction, state, print_cycles, extra_cycles);
+
+    if cx != state.registers.read(CX, true) {
+        state.registers.write(cx, CX, true);
+    }
+
+    // The increment is relative to the start of the instruction.
+    let ip = state.read_ip();
+    if taken {
+        let increment = instruction.dest_operand.unwrap().literal.unwrap();
+        state.write_ip(ip.wrapping_add(increment));
+    } else {
+        state.advance_ip(instruction);
+    }
+}
+
+fn simulate_jmp_call(instruction: &Instruction, state: &mut SimulatorState, print_cycles: bool) {
+    print_instruction_info(instruction, state, print_cycles);
+    let return_ip = state.read_ip().wrapping_add(instruction.length as u16);
+
+    let dest_operand = instruction.dest_operand.as_ref().unwrap();
+    let far = matches!(instruction.op_code, OpCode::CallFar | OpCode::JmpFar);
+
+    // (segment, offset) of the destination, with no segment for near transfers.
+    let (segment, offset): (Option<u16>, u16) = match (far, dest_operand.operand_type) {
+        // Direct near increments are relative to the start of the instruction
+        (false, OperandType::LITERAL) => (
+            None,
+            state.read_ip().wrapping_add(dest_operand.literal.unwrap()),
+        ),
+        (false, _) => (None, read_operand(dest_operand, state)),
+        (true, OperandType::LITERAL) => (
+            instruction.src_operand.unwrap().literal,
+            dest_operand.literal.unwrap(),
+        ),
+        (true, OperandType::EAC) => {
+            let (segment, offset) = read_far_pointer(dest_operand, state);
+            (Some(segment), offset)
+        }
+        (true, _) => panic!("Error: invalid operand for far CALL/JMP"),
+    };
+
+    if matches!(instruction.op_code, OpCode::Call | OpCode::CallFar) {
+        if far {
+            state.push_word(state.registers.read_segment(register::segment::CS));
+        }
+        state.push_word(return_ip);
+    }
+
+    if let Some(segment) = segment {
+        state
+            .registers
+            .write_segment(segment, register::segment::CS);
+    }
+    state.write_ip(offset);
+}
+
+fn simulate_ret(instruction: &Instruction, state: &mut SimulatorState, print_cycles: bool) {
+    print_instruction_info(instruction, state, print_cycles);
+
+    let ip = state.pop_word();
+    if instruction.op_code == OpCode::Retf {
+        let cs = state.pop_word();
+        state.registers.write_segment(cs, register::segment::CS);
+    }
+    state.write_ip(ip);
+
+    if let Some(dest_operand) = instruction.dest_operand.as_ref() {
+        let sp = state.registers.read(register::word::SP, true);
+        state.registers.write(
+            sp.wrapping_add(dest_operand.literal.unwrap()),
+            register::word::SP,
+            true,
+        );
+    }
+}
+
+fn simulate_int(instruction: &Instruction, state: &mut SimulatorState, print_cycles: bool) {
+    // INTO only interrupts (with vector 4) when OF is set.
+    let vector = match instruction.op_code {
+        OpCode::Int => Some(instruction.dest_operand.unwrap().literal.unwrap() as u8),
+        OpCode::Int3 => Some(3),
+        OpCode::Into if state.flags_register.overflow => Some(4),
+        _ => None,
+    };
+
+    let mut extra_cycles = 0;
+    if print_cycles && vector.is_some() {
+        extra_cycles = instruction.time_estimation.unwrap().cycles_taken;
+        state.cycles += extra_cycles;
+    }
+
+    print_instruction_info_with_extra(instruction, state, print_cycles, extra_cycles);
+    // The pushed return address is the next instruction's.
+    state.advance_ip(instruction);
+
+    if let Some(vector) = vector {
+        state.interrupt(vector);
+    }
+}
+
+fn simulate_string(instruction: &Instruction, state: &mut SimulatorState, print_cycles: bool) {
+    use register::word::{AX, CX, DI, SI};
+
+    let op_code = instruction.op_code;
+    let word = instruction.dest_operand.unwrap().register_word.unwrap();
+    let step: u16 = if word { 2 } else { 1 };
+    let delta = if state.flags_register.direction {
+        step.wrapping_neg()
+    } else {
+        step
+    };
+
+    // SI addresses DS unless overridden, DI always addresses ES.
+    let si_operand = [instruction.dest_operand, instruction.src_operand]
+        .into_iter()
+        .flatten()
+        .find(|operand| operand.eac_reg_0 == Some(SI));
+    let src_segment = state.registers.read_segment(
+        si_operand.map_or(register::segment::DS, |operand| get_eac_segment(&operand)),
+    );
+    let dest_segment = state.registers.read_segment(register::segment::ES);
+
+    let read = |state: &SimulatorState, segment: u16, offset: u16| {
+        let address = SimulatorState::physical_address(segment, offset);
+        if word {
+            state.read_mem_word(address)
+        } else {
+            state.read_mem_byte(address) as u16
+        }
+    };
+    let write = |state: &mut SimulatorState, segment: u16, offset: u16, data: u16| {
+        let address = SimulatorState::physical_address(segment, offset);
+        if word {
+            state.write_mem_word(address, data);
+        } else {
+            state.write_mem_byte(address, data as u8);
+        }
+    };
+
+    // Registers are updated locally and written once, so long repetitions print a single change.
+    let mut si = state.registers.read(SI, true);
+    let mut di = state.registers.read(DI, true);
+    let mut cx = state.registers.read(CX, true);
+    let mut ax = state.registers.read(AX, true);
+    let acc_mask: u16 = if word { 0xffff } else { 0xff };
+
+    let rep_prefix = instruction.rep_prefix;
+    let mut repetitions: usize = 0;
+    loop {
+        if rep_prefix.is_some() && cx == 0 {
+            break;
+        }
+
+        match op_code {
+            OpCode::Movs => {
+                let data = read(state, src_segment, si);
+                write(state, dest_segment, di, data);
+                si = si.wrapping_add(delta);
+                di = di.wrapping_add(delta);
+            }
+            OpCode::Cmps => {
+                let src = read(state, src_segment, si);
+                let dest = read(state, dest_segment, di);
+                sub_with_flags(state, src, dest, false, word);
+                si = si.wrapping_add(delta);
+                di = di.wrapping_add(delta);
+            }
+            OpCode::Scas => {
+                let dest = read(state, dest_segment, di);
+                sub_with_flags(state, ax & acc_mask, dest, false, word);
+                di = di.wrapping_add(delta);
+            }
+            OpCode::Lods => {
+                let data = read(state, src_segment, si);
+                ax = (ax & !acc_mask) | data;
+                si = si.wrapping_add(delta);
+            }
+            OpCode::Stos => {
+                write(state, dest_segment, di, ax & acc_mask);
+                di = di.wrapping_add(delta);
             }
-            state.write_ip(new_ip as u16);
+            _ => panic!("Error: invalid opcode for string instruction"),
+        }
+
+        let Some(rep_prefix) = rep_prefix else {
+            break;
+        };
+
+        cx = cx.wrapping_sub(1);
+        repetitions += 1;
+
+        // Only CMPS and SCAS check ZF: REPE stops on a mismatch, REPNE on a match.
+        if matches!(op_code, OpCode::Cmps | OpCode::Scas) {
+            let zero = state.flags_register.zero;
+            match rep_prefix {
+                RepPrefix::Rep if !zero => break,
+                RepPrefix::Repne if zero => break,
+                _ => {}
+            }
+        }
+    }
+
+    let mut extra_cycles = 0;
+    if print_cycles {
+        let time_estimation = instruction.time_estimation.unwrap();
+        extra_cycles = time_estimation.cycles_per_count * repetitions;
+        state.cycles += extra_cycles;
+    }
+
+    print_instruction_info_with_extra(instruction, state, print_cycles, extra_cycles);
+    state.advance_ip(instruction);
+
+    if si != state.registers.read(SI, true) {
+        state.registers.write(si, SI, true);
+    }
+    if di != state.registers.read(DI, true) {
+        state.registers.write(di, DI, true);
+    }
+    if cx != state.registers.read(CX, true) {
+        state.registers.write(cx, CX, true);
+    }
+    if ax != state.registers.read(AX, true) {
+        state.registers.write(ax, AX, true);
+    }
+}
+
+fn simulate_flag_control(
+    instruction: &Instruction,
+    state: &mut SimulatorState,
+    print_cycles: bool,
+) {
+    print_instruction_info(instruction, state, print_cycles);
+    state.advance_ip(instruction);
+
+    let flags = &mut state.flags_register;
+    match instruction.op_code {
+        OpCode::Clc => flags.carry = false,
+        OpCode::Stc => flags.carry = true,
+        OpCode::Cmc => flags.carry = !flags.carry,
+        OpCode::Cld => flags.direction = false,
+        OpCode::Std => flags.direction = true,
+        OpCode::Cli => flags.interrupt = false,
+        OpCode::Sti => flags.interrupt = true,
+        _ => panic!("Error: invalid opcode for flag control instruction"),
+    }
+}
+
+fn simulate_xchg(instruction: &Instruction, state: &mut SimulatorState, print_cycles: bool) {
+    print_instruction_info(instruction, state, print_cycles);
+    state.advance_ip(instruction);
+
+    let dest_operand = instruction.dest_operand.as_ref().unwrap();
+    let src_operand = instruction.src_operand.as_ref().unwrap();
+    let data_dest = read_operand(dest_operand, state);
+    let data_src = read_operand(src_operand, state);
+
+    write_operand(dest_operand, state, data_src);
+    write_operand(src_operand, state, data_dest);
+}
+
+fn simulate_xlat(instruction: &Instruction, state: &mut SimulatorState, print_cycles: bool) {
+    use register::byte::AL;
+
+    print_instruction_info(instruction, state, print_cycles);
+    state.advance_ip(instruction);
+
+    // The table operand is `[BX]`, AL is the unsigned index into it.
+    let table_operand = instruction.src_operand.as_ref().unwrap();
+    let segment = state.registers.read_segment(get_eac_segment(table_operand));
+    let offset =
+        get_eac_address(table_operand, state).wrapping_add(state.registers.read(AL, false));
+
+    let data = state.read_mem_byte(SimulatorState::physical_address(segment, offset));
+    state.registers.write(data as u16, AL, false);
+}
+
+fn simulate_load_address(
+    instruction: &Instruction,
+    state: &mut SimulatorState,
+    print_cycles: bool,
+) {
+    print_instruction_info(instruction, state, print_cycles);
+    state.advance_ip(instruction);
+
+    let dest_operand = instruction.dest_operand.as_ref().unwrap();
+    let src_operand = instruction.src_operand.as_ref().unwrap();
+
+    // LEA only computes the offset, without accessing memory.
+    let (segment, offset) = match instruction.op_code {
+        OpCode::Lea => (None, get_eac_address(src_operand, state)),
+        OpCode::Lds => {
+            let (segment, offset) = read_far_pointer(src_operand, state);
+            (Some((segment, register::segment::DS)), offset)
         }
-        _ => {}
+        OpCode::Les => {
+            let (segment, offset) = read_far_pointer(src_operand, state);
+            (Some((segment, register::segment::ES)), offset)
+        }
+        _ => panic!("Error: invalid opcode for LEA/LDS/LES instruction"),
     };
+
+    write_operand(dest_operand, state, offset);
+    if let Some((segment, segment_register)) = segment {
+        state.registers.write_segment(segment, segment_register);
+    }
+}
+
+/// Simulates LAHF/SAHF/PUSHF/POPF, moving the flags as laid out in the FLAGS word.
+fn simulate_flags_transfer(
+    instruction: &Instruction,
+    state: &mut SimulatorState,
+    print_cycles: bool,
+) {
+    use register::byte::AH;
+
+    print_instruction_info(instruction, state, print_cycles);
+    state.advance_ip(instruction);
+
+    let flags = state.flags_register.to_word();
+    match instruction.op_code {
+        // AH gets SF, ZF, AF, PF and CF, the low byte of FLAGS.
+        OpCode::Lahf => state.registers.write(flags & 0xff, AH, false),
+        OpCode::Sahf => {
+            let ah = state.registers.read(AH, false);
+            state.flags_register.set_from_word((flags & 0xff00) | ah);
+        }
+        OpCode::Pushf => state.push_word(flags),
+        OpCode::Popf => {
+            let data = state.pop_word();
+            state.flags_register.set_from_word(data);
+        }
+        _ => panic!("Error: invalid opcode for LAHF/SAHF/PUSHF/POPF instruction"),
+    }
+}
+
+fn simulate_in_out(instruction: &Instruction, state: &mut SimulatorState, print_cycles: bool) {
+    print_instruction_info(instruction, state, print_cycles);
+    state.advance_ip(instruction);
+
+    let dest_operand = instruction.dest_operand.as_ref().unwrap();
+    let src_operand = instruction.src_operand.as_ref().unwrap();
+
+    // The port is an immediate byte or DX, the other operand is the accumulator.
+    if instruction.op_code == OpCode::In {
+        let port = read_operand(src_operand, state);
+        let word = dest_operand.register_word.unwrap();
+        let data = state.read_port(port, word);
+        write_operand(dest_operand, state, data);
+    } else {
+        let port = read_operand(dest_operand, state);
+        let word = src_operand.register_word.unwrap();
+        let data = read_operand(src_operand, state);
+        state.write_port(port, word, data);
+    }
+}
+
+fn simulate_push(instruction: &Instruction, state: &mut SimulatorState, print_cycles: bool) {
+    print_instruction_info(instruction, state, print_cycles);
+    state.advance_ip(instruction);
+
+    let src_operand = instruction.dest_operand.as_ref().unwrap();
+    let mut data = read_operand(src_operand, state);
+
+    // The 8086 decrements SP before reading the operand, so `PUSH SP` pushes the new value.
+    if matches!(src_operand.operand_type, OperandType::REGISTER)
+        && src_operand.register == Some(register::word::SP)
+    {
+        data = data.wrapping_sub(2);
+    }
+
+    state.push_word(data);
+}
+
+fn simulate_pop(instruction: &Instruction, state: &mut SimulatorState, print_cycles: bool) {
+    print_instruction_info(instruction, state, print_cycles);
+    state.advance_ip(instruction);
+
+    let data = state.pop_word();
+
+    let dest_operand = instruction.dest_operand.as_ref().unwrap();
+    write_operand(dest_operand, state, data);
+}
+
+/// Returns the segment register an effective address is relative to: the override prefix if
+/// any, SS for BP based addresses and DS otherwise.
+fn get_eac_segment(operand: &InstructionOperand) -> u8 {
+    match operand.eac_segment {
+        Some(segment) => segment,
+        None if operand.eac_reg_0 == Some(register::word::BP) => register::segment::SS,
+        None => register::segment::DS,
+    }
+}
+
+/// Returns the 20-bit physical address of an effective address operand.
+fn get_eac_physical_address(operand: &InstructionOperand, state: &SimulatorState) -> usize {
+    let segment = state.registers.read_segment(get_eac_segment(operand));
+    SimulatorState::physical_address(segment, get_eac_address(operand, state))
+}
+
+/// Reads the far pointer (offset word, then segment word) stored at a memory operand.
+/// Returns `(segment, offset)`.
+fn read_far_pointer(operand: &InstructionOperand, state: &SimulatorState) -> (u16, u16) {
+    let segment = state.registers.read_segment(get_eac_segment(operand));
+    let offset = get_eac_address(operand, state);
+
+    (
+        state.read_mem_word(SimulatorState::physical_address(
+            segment,
+            offset.wrapping_add(2),
+        )),
+        state.read_mem_word(SimulatorState::physical_address(segment, offset)),
+    )
+}
+
+/// Returns the effective address (offset) of an EAC operand.
+fn get_eac_address(operand: &InstructionOperand, state: &SimulatorState) -> u16 {
+    let mut address = operand.eac_displacement.unwrap_or_default();
+    if let Some(reg) = operand.eac_reg_0 {
+        address = address.wrapping_add(state.registers.read(reg, true));
+    }
+    if let Some(reg) = operand.eac_reg_1 {
+        address = address.wrapping_add(state.registers.read(reg, true));
+    }
+
+    address
+}
+
+/// Reads the current value of an operand, with the width given by `register_word`.
+fn read_operand(operand: &InstructionOperand, state: &SimulatorState) -> u16 {
+    match operand.operand_type {
+        OperandType::REGISTER => state
+            .registers
+            .read(operand.register.unwrap(), operand.register_word.unwrap()),
+        OperandType::SEGMENT => state.registers.read_segment(operand.register.unwrap()),
+        OperandType::EAC => {
+            let address = get_eac_physical_address(operand, state);
+            if operand.register_word.unwrap() {
+                state.read_mem_word(address)
+            } else {
+                state.read_mem_byte(address) as u16
+            }
+        }
+        OperandType::LITERAL => operand.literal.unwrap(),
+    }
+}
+
+/// Writes `data` into an operand, with the width given by `register_word`.
+fn write_operand(operand: &InstructionOperand, state: &mut SimulatorState, data: u16) {
+    match operand.operand_type {
+        OperandType::REGISTER => state.registers.write(
+            data,
+            operand.register.unwrap(),
+            operand.register_word.unwrap(),
+        ),
+        OperandType::SEGMENT => state
+            .registers
+            .write_segment(data, operand.register.unwrap()),
+        OperandType::EAC => {
+            let address = get_eac_physical_address(operand, state);
+            if operand.register_word.unwrap() {
+                state.write_mem_word(address, data);
+            } else {
+                state.write_mem_byte(address, data as u8);
+            }
+        }
+        OperandType::LITERAL => panic!("Error: can't write into a literal operand"),
+    }
 }
@@ -1,20 +1,44 @@
-use std::io::Write;
+use std::{collections::HashMap, io::Write};
 
-use crate::register::{self, util::get_register_string};
+use crate::{
+    decoder::decode_instruction,
+    program::instruction::{self, Instruction},
+    register::{self, util::get_register_string},
+    simulator::{decode_cache::DecodeCache, io_bus::IoBus},
+};
 
 const MEMORY_SIZE: usize = 1024 * 1024;
+const ADDRESS_MASK: usize = MEMORY_SIZE - 1;
 const MEMORY_DUMP_FILE: &str = "memory.data";
 
+/// Segment the program image is loaded at, keeping it clear of the interrupt vector table.
+pub const PROGRAM_SEGMENT: u16 = 0x1000;
+/// Bytes fetched for decoding an instruction, enough for the longest one and a few prefixes.
+const FETCH_LENGTH: usize = 16;
+
+/// Host code run for an interrupt instead of the guest handler in the vector table.
+pub type InterruptHandler = Box<dyn FnMut(&mut SimulatorState)>;
+
 pub struct SimulatorState {
     pub registers: SimulatorRegisters,
     pub flags_register: SimulatorFlagsRegister,
 
     pub cycles: usize,
 
+    /// Set when the simulation can't continue, stopping the simulator loop.
+    pub halted: bool,
+
     ip: u16,
 
-    // TODO: load the running 8086 program into the same addressable memory block.
+    interrupt_handlers: HashMap<u8, InterruptHandler>,
+    io_bus: Box<dyn IoBus>,
+
     memory: Vec<u8>,
+    /// Segment and length of the loaded program image, the simulation ends when execution runs
+    /// past its end.
+    program_segment: u16,
+    program_length: usize,
+    decode_cache: Option<DecodeCache>,
 }
 
 pub struct SimulatorRegisters {
@@ -27,32 +51,111 @@ pub struct SimulatorRegisters {
     bp: u16,
     si: u16,
     di: u16,
+
+    es: u16,
+    cs: u16,
+    ss: u16,
+    ds: u16,
 }
 
+#[derive(Clone, Copy, PartialEq)]
 pub struct SimulatorFlagsRegister {
-    pub sign: bool,
+    pub carry: bool,
+    pub parity: bool,
+    /// AF: carry out of (or borrow into) the low nibble, used by the decimal adjust instructions.
+    pub auxiliary_carry: bool,
     pub zero: bool,
+    pub sign: bool,
+    pub trap: bool,
+    pub interrupt: bool,
+    pub direction: bool,
+    pub overflow: bool,
 }
 
 impl SimulatorState {
-    pub fn new() -> Self {
+    pub fn new(io_bus: Box<dyn IoBus>) -> Self {
         let registers = SimulatorRegisters::new();
         let flags_register = SimulatorFlagsRegister::new();
 
         let cycles = 0;
+        let halted = false;
 
         let ip = 0;
+        let interrupt_handlers = HashMap::new();
         let memory = vec![0; MEMORY_SIZE];
+        let program_segment = 0;
+        let program_length = 0;
+        let decode_cache = None;
 
         Self {
             registers,
             flags_register,
 
             cycles,
+            halted,
 
             ip,
+            interrupt_handlers,
+            io_bus,
             memory,
+            program_segment,
+            program_length,
+            decode_cache,
+        }
+    }
+
+    /// Copies a program image into memory at `segment:0000` and points CS:IP at its start.
+    /// Like a COM program, the data, extra and stack segments are the program segment too.
+    pub fn load_program(&mut self, bytes: &[u8], segment: u16) {
+        let start = Self::physical_address(segment, 0);
+        for (i, byte) in bytes.iter().enumerate() {
+            self.write_mem_byte(start + i, *byte);
+        }
+        self.program_segment = segment;
+        self.program_length = bytes.len();
+
+        for segment_register in [
+            register::segment::CS,
+            register::segment::DS,
+            register::segment::ES,
+            register::segment::SS,
+        ] {
+            self.registers.write_segment(segment, segment_register);
         }
+        self.ip = 0;
+    }
+
+    /// Caches decoded instructions, see `DecodeCache`.
+    pub fn enable_decode_cache(&mut self) {
+        self.decode_cache = Some(DecodeCache::new());
+    }
+
+    /// Decodes the instruction at CS:IP from memory, or returns an end of program instruction
+    /// when execution ran past the end of the program image in its segment. Code anywhere else
+    /// in memory (copied there, or a far call or interrupt target) runs like the image.
+    pub fn fetch_instruction(&mut self) -> Result<Instruction, ()> {
+        let cs = self.registers.read_segment(register::segment::CS);
+        if cs == self.program_segment && self.ip as usize >= self.program_length {
+            return Ok(instruction::END_OF_PROGRAM.clone());
+        }
+
+        let address = Self::physical_address(cs, self.ip);
+
+        if let Some(instruction) = self.decode_cache.as_ref().and_then(|c| c.get(address)) {
+            return Ok(instruction.clone());
+        }
+
+        // Instruction bytes wrap around inside the code segment.
+        let bytes: Vec<u8> = (0..FETCH_LENGTH)
+            .map(|i| self.read_mem_byte(Self::physical_address(cs, self.ip.wrapping_add(i as u16))))
+            .collect();
+        let (_, instruction) = decode_instruction(&bytes, 0)?;
+
+        if let Some(decode_cache) = self.decode_cache.as_mut() {
+            decode_cache.insert(address, instruction.clone());
+        }
+
+        Ok(instruction)
     }
 
     pub fn read_ip(&self) -> u16 {
@@ -64,32 +167,154 @@ impl SimulatorState {
         self.ip = ip;
     }
 
+    /// Moves IP past `instruction`, wrapping around inside the code segment.
+    pub fn advance_ip(&mut self, instruction: &Instruction) {
+        self.write_ip(self.ip.wrapping_add(instruction.length as u16));
+    }
+
     pub fn print_ip(&self) {
         println!("  IP: 0x{:04x} ({})", self.ip, self.ip);
     }
 
+    /// Returns the 20-bit physical address for a `segment:offset` pair.
+    pub fn physical_address(segment: u16, offset: u16) -> usize {
+        ((segment as usize) * 16 + offset as usize) & ADDRESS_MASK
+    }
+
     pub fn read_mem_byte(&self, address: usize) -> u8 {
-        self.memory[address]
+        self.memory[address & ADDRESS_MASK]
     }
 
     pub fn read_mem_word(&self, address_lo: usize) -> u16 {
-        self.read_mem_byte(address_lo) as u16 + self.read_mem_byte(address_lo + 1) as u16
+        self.read_mem_byte(address_lo) as u16 + self.read_mem_byte(address_lo + 1) as u16 * 256
     }
 
     pub fn write_mem_byte(&mut self, address: usize, data: u8) {
+        let address = address & ADDRESS_MASK;
         self.memory[address] = data;
+
+        if let Some(decode_cache) = self.decode_cache.as_mut() {
+            decode_cache.invalidate(address);
+        }
     }
 
     pub fn write_mem_word(&mut self, address_lo: usize, data: u16) {
         let bytes = data.to_le_bytes();
-        self.write_mem_byte(address_lo as usize, bytes[0]);
-        self.write_mem_byte(address_lo as usize + 1, bytes[1]);
+        self.write_mem_byte(address_lo, bytes[0]);
+        self.write_mem_byte(address_lo + 1, bytes[1]);
+    }
+
+    /// Decrements SP by two and writes a word at the new top of the stack (`SS:SP`).
+    pub fn push_word(&mut self, data: u16) {
+        let sp = self
+            .registers
+            .read(register::word::SP, true)
+            .wrapping_sub(2);
+        self.registers.write(sp, register::word::SP, true);
+        self.write_stack_word(sp, data);
+    }
+
+    /// Reads the word at the top of the stack (`SS:SP`) and increments SP by two.
+    pub fn pop_word(&mut self) -> u16 {
+        let sp = self.registers.read(register::word::SP, true);
+        let data = self.read_stack_word(sp);
+        self.registers
+            .write(sp.wrapping_add(2), register::word::SP, true);
+        data
+    }
+
+    /// Reads a word from the stack segment. The high byte wraps around inside the segment.
+    fn read_stack_word(&self, offset: u16) -> u16 {
+        let ss = self.registers.read_segment(register::segment::SS);
+        let lo = self.read_mem_byte(Self::physical_address(ss, offset));
+        let hi = self.read_mem_byte(Self::physical_address(ss, offset.wrapping_add(1)));
+        u16::from_le_bytes([lo, hi])
+    }
+
+    /// Writes a word into the stack segment. The high byte wraps around inside the segment.
+    fn write_stack_word(&mut self, offset: u16, data: u16) {
+        let ss = self.registers.read_segment(register::segment::SS);
+        let bytes = data.to_le_bytes();
+        self.write_mem_byte(Self::physical_address(ss, offset), bytes[0]);
+        self.write_mem_byte(Self::physical_address(ss, offset.wrapping_add(1)), bytes[1]);
+    }
+
+    /// Registers a host handler for interrupt `vector`, replacing any previous one.
+    pub fn set_interrupt_handler(&mut self, vector: u8, handler: InterruptHandler) {
+        self.interrupt_handlers.insert(vector, handler);
+    }
+
+    /// Raises interrupt `vector`: pushes FLAGS, CS and IP, clears IF and TF and jumps to the
+    /// handler in the interrupt vector table at `0000:0000`.
+    /// Vectors with a host handler run it instead and continue at the current IP, as if the
+    /// guest handler had returned straight away.
+    pub fn interrupt(&mut self, vector: u8) {
+        // Taken out of the map while it runs, so the handler can borrow the whole state.
+        if let Some(mut handler) = self.interrupt_handlers.remove(&vector) {
+            println!("  Interrupt {} (host handler)", vector);
+            handler(self);
+            self.interrupt_handlers.entry(vector).or_insert(handler);
+            return;
+        }
+
+        println!("  Interrupt {}", vector);
+
+        let address = vector as usize * 4;
+        let handler_ip = self.read_mem_word(address);
+        let handler_cs = self.read_mem_word(address + 2);
+
+        // With nothing installed in the vector table the program would jump to 0000:0000.
+        if handler_ip == 0 && handler_cs == 0 {
+            println!("Error: no handler installed for interrupt {}", vector);
+            self.halted = true;
+            return;
+        }
+
+        self.push_word(self.flags_register.to_word());
+        self.push_word(self.registers.read_segment(register::segment::CS));
+        self.push_word(self.ip);
+
+        self.flags_register.interrupt = false;
+        self.flags_register.trap = false;
+
+        self.registers
+            .write_segment(handler_cs, register::segment::CS);
+        self.write_ip(handler_ip);
+    }
+
+    /// Returns from an interrupt handler, popping IP, CS and FLAGS.
+    pub fn interrupt_return(&mut self) {
+        let ip = self.pop_word();
+        let cs = self.pop_word();
+        let flags = self.pop_word();
+
+        self.registers.write_segment(cs, register::segment::CS);
+        self.flags_register.set_from_word(flags);
+        self.write_ip(ip);
+    }
+
+    /// Reads a byte or word from I/O port `port` (IN).
+    pub fn read_port(&mut self, port: u16, word: bool) -> u16 {
+        if word {
+            self.io_bus.read_word(port)
+        } else {
+            self.io_bus.read_byte(port) as u16
+        }
+    }
+
+    /// Writes a byte or word to I/O port `port` (OUT).
+    pub fn write_port(&mut self, port: u16, word: bool, data: u16) {
+        if word {
+            self.io_bus.write_word(port, data);
+        } else {
+            self.io_bus.write_byte(port, data as u8);
+        }
     }
 
     pub fn dump_memory(&self) -> std::io::Result<()> {
         println!("Dumping memory...");
 
-        let mut output_file = std::fs::File::create(&MEMORY_DUMP_FILE)?;
+        let mut output_file = std::fs::File::create(MEMORY_DUMP_FILE)?;
         output_file.write_all(&self.memory)?;
         output_file.sync_all()?;
 
@@ -110,110 +335,110 @@ impl SimulatorRegisters {
             bp: 0,
             si: 0,
             di: 0,
+
+            es: 0,
+            cs: 0,
+            ss: 0,
+            ds: 0,
         }
     }
 
     /// Read data from a register.
+    /// Byte registers are views of AX..DX: AL..BL the low bytes and AH..BH the high bytes.
     pub fn read(&self, reg_bytes: u8, is_word: bool) -> u16 {
-        match (is_word, reg_bytes) {
-            (true, register::word::AX) => self.ax,
-            (true, register::word::BX) => self.bx,
-            (true, register::word::CX) => self.cx,
-            (true, register::word::DX) => self.dx,
-            (true, register::word::SP) => self.sp,
-            (true, register::word::BP) => self.bp,
-            (true, register::word::SI) => self.si,
-            (true, register::word::DI) => self.di,
-            (true, _) => panic!("Error: register not recognized when trying to read"),
-            // TODO: manager byte registers
-            (false, _) => todo!("Error: reading from byte registers not implemented"),
-        }
-    }
-
-    /// Writes data into a register.
+        if is_word {
+            return *self.word_register(reg_bytes);
+        }
+
+        let word_data = *self.word_register(reg_bytes & 0b011);
+        if reg_bytes & 0b100 != 0 {
+            word_data >> 8
+        } else {
+            word_data & 0xff
+        }
+    }
+
+    /// Writes data into a register. Byte registers only take the low byte of `data`.
     pub fn write(&mut self, data: u16, reg_bytes: u8, is_word: bool) {
-        let old_data: u16;
+        let old_data = self.read(reg_bytes, is_word);
 
         if is_word {
-            match reg_bytes {
-                register::word::AX => {
-                    old_data = self.ax;
-                    self.ax = data;
-                }
-                register::word::BX => {
-                    old_data = self.bx;
-                    self.bx = data;
-                }
-                register::word::CX => {
-                    old_data = self.cx;
-                    self.cx = data;
-                }
-                register::word::DX => {
-                    old_data = self.dx;
-                    self.dx = data;
-                }
-                register::word::SP => {
-                    old_data = self.sp;
-                    self.sp = data;
-                }
-                register::word::BP => {
-                    old_data = self.bp;
-                    self.bp = data;
-                }
-                register::word::SI => {
-                    old_data = self.si;
-                    self.si = data;
-                }
-                register::word::DI => {
-                    old_data = self.di;
-                    self.di = data;
-                }
-                // TODO: use a recognizable default value
-                _ => old_data = 0,
-            }
-        }
-        // TODO: manage byte registers
-        else {
-            match reg_bytes {
-                register::byte::AL => {
-                    old_data = self.ax;
-                    // self.ax = data;
-                }
-                register::byte::BL => {
-                    old_data = self.bx;
-                    // self.bx = data;
-                }
-                register::byte::CL => {
-                    old_data = self.cx;
-                    // self.cx = data;
-                }
-                register::byte::DL => {
-                    old_data = self.dx;
-                    // self.dx = data;
-                }
-                register::byte::AH => {
-                    old_data = self.sp;
-                    // self.sp = data;
-                }
-                register::byte::BH => {
-                    old_data = self.bp;
-                    // self.bp = data;
-                }
-                register::byte::CH => {
-                    old_data = self.si;
-                    // self.si = data;
-                }
-                register::byte::DH => {
-                    old_data = self.di;
-                    // self.di = data;
-                }
-                // TODO: use a recognizable default value
-                _ => old_data = 0,
-            }
+            *self.word_register_mut(reg_bytes) = data;
+        } else {
+            let high = reg_bytes & 0b100 != 0;
+            let word_register = self.word_register_mut(reg_bytes & 0b011);
+            *word_register = if high {
+                (*word_register & 0x00ff) | (data & 0xff) << 8
+            } else {
+                (*word_register & 0xff00) | (data & 0xff)
+            };
         }
 
         let reg_string = get_register_string(reg_bytes, is_word);
 
+        if is_word {
+            println!("  {}: 0x{:04x} -> 0x{:04x}", reg_string, old_data, data);
+        } else {
+            println!(
+                "  {}: 0x{:02x} -> 0x{:02x}",
+                reg_string,
+                old_data,
+                data & 0xff
+            );
+        }
+    }
+
+    fn word_register(&self, reg_bytes: u8) -> &u16 {
+        match reg_bytes {
+            register::word::AX => &self.ax,
+            register::word::BX => &self.bx,
+            register::word::CX => &self.cx,
+            register::word::DX => &self.dx,
+            register::word::SP => &self.sp,
+            register::word::BP => &self.bp,
+            register::word::SI => &self.si,
+            register::word::DI => &self.di,
+            _ => panic!("Error: register not recognized"),
+        }
+    }
+
+    fn word_register_mut(&mut self, reg_bytes: u8) -> &mut u16 {
+        match reg_bytes {
+            register::word::AX => &mut self.ax,
+            register::word::BX => &mut self.bx,
+            register::word::CX => &mut self.cx,
+            register::word::DX => &mut self.dx,
+            register::word::SP => &mut self.sp,
+            register::word::BP => &mut self.bp,
+            register::word::SI => &mut self.si,
+            register::word::DI => &mut self.di,
+            _ => panic!("Error: register not recognized"),
+        }
+    }
+
+    /// Read data from a segment register.
+    pub fn read_segment(&self, reg_bytes: u8) -> u16 {
+        match reg_bytes {
+            register::segment::ES => self.es,
+            register::segment::CS => self.cs,
+            register::segment::SS => self.ss,
+            register::segment::DS => self.ds,
+            _ => panic!("Error: segment register not recognized when trying to read"),
+        }
+    }
+
+    /// Writes data into a segment register.
+    pub fn write_segment(&mut self, data: u16, reg_bytes: u8) {
+        let old_data = match reg_bytes {
+            register::segment::ES => std::mem::replace(&mut self.es, data),
+            register::segment::CS => std::mem::replace(&mut self.cs, data),
+            register::segment::SS => std::mem::replace(&mut self.ss, data),
+            register::segment::DS => std::mem::replace(&mut self.ds, data),
+            _ => panic!("Error: segment register not recognized when trying to write"),
+        };
+
+        let reg_string = register::segment::get_str(reg_bytes);
+
         println!("  {}: 0x{:04x} -> 0x{:04x}", reg_string, old_data, data);
     }
 
@@ -242,31 +467,110 @@ impl SimulatorRegisters {
         if !skip_zero || self.di != 0 {
             println!("  DI: 0x{:04x} ({})", self.di, self.di);
         }
+        if !skip_zero || self.es != 0 {
+            println!("  ES: 0x{:04x} ({})", self.es, self.es);
+        }
+        if !skip_zero || self.cs != 0 {
+            println!("  CS: 0x{:04x} ({})", self.cs, self.cs);
+        }
+        if !skip_zero || self.ss != 0 {
+            println!("  SS: 0x{:04x} ({})", self.ss, self.ss);
+        }
+        if !skip_zero || self.ds != 0 {
+            println!("  DS: 0x{:04x} ({})", self.ds, self.ds);
+        }
     }
 }
 
 impl SimulatorFlagsRegister {
     pub fn new() -> Self {
         Self {
+            carry: false,
+            parity: false,
+            auxiliary_carry: false,
             zero: false,
             sign: false,
+            trap: false,
+            interrupt: false,
+            direction: false,
+            overflow: false,
         }
     }
 
-    pub fn print(&self) {
-        let mut flags_string = String::new();
+    /// Packs the flags into the 16-bit FLAGS word layout. Unused bits read as set on the 8086.
+    pub fn to_word(self) -> u16 {
+        let mut word: u16 = 0xf002;
+
+        word |= self.carry as u16;
+        word |= (self.parity as u16) << 2;
+        word |= (self.auxiliary_carry as u16) << 4;
+        word |= (self.zero as u16) << 6;
+        word |= (self.sign as u16) << 7;
+        word |= (self.trap as u16) << 8;
+        word |= (self.interrupt as u16) << 9;
+        word |= (self.direction as u16) << 10;
+        word |= (self.overflow as u16) << 11;
+
+        word
+    }
 
-        if self.sign {
-            flags_string.push('S');
-        }
-        if self.zero {
-            flags_string.push('Z');
-        }
+    /// Unpacks the flags from the 16-bit FLAGS word layout.
+    pub fn set_from_word(&mut self, word: u16) {
+        self.carry = word & 1 != 0;
+        self.parity = word & (1 << 2) != 0;
+        self.auxiliary_carry = word & (1 << 4) != 0;
+        self.zero = word & (1 << 6) != 0;
+        self.sign = word & (1 << 7) != 0;
+        self.trap = word & (1 << 8) != 0;
+        self.interrupt = word & (1 << 9) != 0;
+        self.direction = word & (1 << 10) != 0;
+        self.overflow = word & (1 << 11) != 0;
+    }
+
+    /// Sets SF, ZF and PF from a byte or word result.
+    /// PF only looks at the low 8 bits of the result.
+    pub fn set_sign_zero_parity(&mut self, result: u16, word: bool) {
+        let sign_bit = if word { 0x8000 } else { 0x80 };
+        let mask = if word { 0xffff } else { 0xff };
+
+        self.sign = result & sign_bit != 0;
+        self.zero = result & mask == 0;
+        self.parity = (result as u8).count_ones().is_multiple_of(2);
+    }
+
+    /// Returns the set flags as letters in FLAGS bit order (`CPAZSTIDO`), the format used by the
+    /// course reference traces.
+    pub fn get_string(&self) -> String {
+        let flags = [
+            (self.carry, 'C'),
+            (self.parity, 'P'),
+            (self.auxiliary_carry, 'A'),
+            (self.zero, 'Z'),
+            (self.sign, 'S'),
+            (self.trap, 'T'),
+            (self.interrupt, 'I'),
+            (self.direction, 'D'),
+            (self.overflow, 'O'),
+        ];
+
+        flags
+            .iter()
+            .filter(|(set, _)| *set)
+            .map(|(_, letter)| letter)
+            .collect()
+    }
 
-        if flags_string.len() == 0 {
+    pub fn print(&self) {
+        let mut flags_string = self.get_string();
+        if flags_string.is_empty() {
             flags_string.push('-');
         }
 
         println!("  Flags: {}", flags_string);
     }
+
+    /// Prints the flags changed by an instruction as `old->new`.
+    pub fn print_change(&self, old_flags: &Self) {
+        println!("  Flags: {}->{}", old_flags.get_string(), self.get_string());
+    }
 }
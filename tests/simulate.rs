@@ -0,0 +1,76 @@
+use std::{fs, path::PathBuf, process::Command};
+
+/// Writes `bytes` into a program file named `name` and simulates it with `options`, returning
+/// the output.
+fn simulate(name: &str, bytes: &[u8], options: &[&str]) -> String {
+    let file: PathBuf = std::env::temp_dir().join(format!("perfaware_8086_{}.bin", name));
+    fs::write(&file, bytes).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_perfaware_8086"))
+        .args(options)
+        .arg("simulate")
+        .arg(&file)
+        .output()
+        .unwrap();
+    fs::remove_file(&file).unwrap();
+
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Returns the final state printed at the end of the simulation output.
+fn final_state(output: &str) -> &str {
+    output.split("Final state").nth(1).unwrap()
+}
+
+/// Runs `MOV AX, 1`, patches its immediate through DS and runs it again.
+const SELF_MODIFYING: &[u8] = &[
+    0xb9, 0x02, 0x00, //       mov cx, 2
+    0xb8, 0x01, 0x00, //       patch: mov ax, 1
+    0xc6, 0x06, 0x04, 0x00, 0x05, // mov byte [patch + 1], 5
+    0xe2, 0xf6, //             loop patch
+    0xf4, //                   hlt
+];
+
+#[test]
+fn self_modifying_code() {
+    let output = simulate("self_modifying", SELF_MODIFYING, &[]);
+    assert!(final_state(&output).contains("AX: 0x0005"), "{}", output);
+}
+
+#[test]
+fn self_modifying_code_with_cache() {
+    let output = simulate("self_modifying_cache", SELF_MODIFYING, &["cache"]);
+    assert!(final_state(&output).contains("AX: 0x0005"), "{}", output);
+}
+
+#[test]
+fn code_copied_out_of_the_program() {
+    let bytes = [
+        0xb8, 0x00, 0x20, //       mov ax, 0x2000
+        0x8e, 0xc0, //             mov es, ax
+        0xbe, 0x14, 0x00, //       mov si, code
+        0x31, 0xff, //             xor di, di
+        0xb9, 0x04, 0x00, //       mov cx, 4
+        0xf3, 0xa4, //             rep movsb
+        0xea, 0x00, 0x00, 0x00, 0x20, // jmp 0x2000:0
+        0xb8, 0x07, 0x00, //       code: mov ax, 7
+        0xf4, //                   hlt
+    ];
+    let output = simulate("copied_code", &bytes, &[]);
+    assert!(output.contains("Simulation halted"), "{}", output);
+    assert!(final_state(&output).contains("CS: 0x2000"), "{}", output);
+    assert!(final_state(&output).contains("AX: 0x0007"), "{}", output);
+}
+
+#[test]
+fn dos_prints_a_string_of_the_program() {
+    let bytes = [
+        0xba, 0x08, 0x00, // mov dx, message
+        0xb4, 0x09, //       mov ah, 9
+        0xcd, 0x21, //       int 0x21
+        0xf4, //             hlt
+        b'H', b'i', b'$', // message: db 'Hi$'
+    ];
+    let output = simulate("dos_print", &bytes, &["dos"]);
+    assert!(output.contains("DOS output: \"Hi\""), "{}", output);
+}